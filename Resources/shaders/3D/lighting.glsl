@@ -1,22 +1,111 @@
+//must match MAX_POINT_LIGHTS and MAX_DIRECTIONAL_LIGHTS in uniform.rs
+const int MAX_POINT_LIGHTS = 8;
+const int MAX_DIRECTIONAL_LIGHTS = 4;
+
 //vec4 is used here instead of vec3, because my drivers fail to align it correctly
+struct PointLight {
+    vec4 position;
+    vec4 color;
+    float linear_attenuation;
+    float quadratic_attenuation;
+};
+struct DirectionalLight {
+    vec4 direction;//the direction the light travels in
+    vec4 color;
+};
+struct ShadowTransform {
+    mat3 matrix;
+    vec3 translation;
+};
+
 layout (std140) uniform fragment_uniforms {
-    vec4 light_position;
-    vec4 light_color;
+    PointLight point_lights[MAX_POINT_LIGHTS];
+    DirectionalLight directional_lights[MAX_DIRECTIONAL_LIGHTS];
+    ShadowTransform shadow_transform;//from world space to shadow map coordinates (u, v, depth), all in [0, 1] inside the shadow map
+    vec4 camera_position;
     vec4 light_ambient_color;
-    float light_linear_attenuation;
-    float light_quadratic_attenuation;
+    int point_light_count;
+    int directional_light_count;
+    int has_shadow;//whether directional_lights[0] casts shadows
 };
+uniform sampler2D shadow_map;
 
-float get_light_attenuation() {
-    float dist = distance(light_position.xyz, v_in.world_position);
-    return 1.0 / (1.0 + light_linear_attenuation * dist + light_quadratic_attenuation * dist*dist); 
+//@include common/brdf.glsl
+
+float get_shadow_factor() {
+    vec3 coordinates = shadow_transform.matrix * v_in.world_position + shadow_transform.translation;
+    if (any(lessThan(coordinates, vec3(0.0))) || any(greaterThan(coordinates, vec3(1.0)))) {
+        return 1.0;
+    }
+
+    //percentage closer filtering
+    vec2 texel_size = 1.0 / vec2(textureSize(shadow_map, 0));
+    float lit = 0.0;
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            float occluder_depth = texture(shadow_map, coordinates.xy + vec2(x, y) * texel_size).r;
+            lit += coordinates.z > occluder_depth ? 0.0 : 1.0;
+        }
+    }
+    return lit / 9.0;
+}
+
+//the fraction of directional_lights[i] that isn't blocked
+float get_directional_light_visibility(int i) {
+    return (i == 0 && has_shadow != 0) ? get_shadow_factor() : 1.0;
+}
+
+float get_light_attenuation(PointLight light, float dist) {
+    return 1.0 / (1.0 + light.linear_attenuation * dist + light.quadratic_attenuation * dist*dist);
 }
 
 vec3 get_lit_color(vec3 albedo) {
-    vec3 light_direction = normalize(light_position.xyz - v_in.world_position);
-    float diffuse_strength = max(dot(normalize(v_in.world_normal), light_direction), 0.0);
-    vec3 diffuse_light = diffuse_strength * light_color.xyz;
+    vec3 normal = normalize(v_in.world_normal);
+    vec3 light = light_ambient_color.xyz;
+
+    for (int i = 0; i < point_light_count; i++) {
+        vec3 to_light = point_lights[i].position.xyz - v_in.world_position;
+        float dist = length(to_light);
+        float diffuse_strength = max(dot(normal, to_light / dist), 0.0);
+        light += diffuse_strength * point_lights[i].color.xyz * get_light_attenuation(point_lights[i], dist);
+    }
+    for (int i = 0; i < directional_light_count; i++) {
+        float diffuse_strength = max(dot(normal, -directional_lights[i].direction.xyz), 0.0);
+        light += diffuse_strength * directional_lights[i].color.xyz * get_directional_light_visibility(i);
+    }
 
-    vec3 light = (light_ambient_color.xyz + diffuse_light) * get_light_attenuation();
     return light * albedo;
 }
+
+vec3 get_reflected_light(vec3 light_color, vec3 normal, vec3 to_camera, vec3 to_light, vec3 albedo, float roughness, float metalness) {
+    float n_dot_l = dot(normal, to_light);
+    if (n_dot_l <= 0.0) {
+        return vec3(0.0);
+    }
+
+    vec3 halfway = normalize(to_light + to_camera);
+    float n_dot_v = max(dot(normal, to_camera), 0.0);
+    float n_dot_h = max(dot(normal, halfway), 0.0);
+    float h_dot_v = max(dot(halfway, to_camera), 0.0);
+    return light_color * get_reflected_fraction(n_dot_l, n_dot_v, n_dot_h, h_dot_v, albedo, roughness, metalness);
+}
+
+vec3 get_pbr_lit_color(vec3 albedo, float roughness, float metalness) {
+    vec3 normal = normalize(v_in.world_normal);
+    vec3 to_camera = normalize(camera_position.xyz - v_in.world_position);
+    vec3 light = light_ambient_color.xyz * albedo;
+
+    for (int i = 0; i < point_light_count; i++) {
+        vec3 to_light = point_lights[i].position.xyz - v_in.world_position;
+        float dist = length(to_light);
+        vec3 light_color = point_lights[i].color.xyz * get_light_attenuation(point_lights[i], dist);
+        light += get_reflected_light(light_color, normal, to_camera, to_light / dist, albedo, roughness, metalness);
+    }
+    for (int i = 0; i < directional_light_count; i++) {
+        vec3 to_light = -directional_lights[i].direction.xyz;
+        vec3 light_color = directional_lights[i].color.xyz * get_directional_light_visibility(i);
+        light += get_reflected_light(light_color, normal, to_camera, to_light, albedo, roughness, metalness);
+    }
+
+    return light;
+}
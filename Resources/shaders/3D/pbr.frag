@@ -0,0 +1,20 @@
+#version 330 core
+
+uniform vec3 albedo;
+uniform float roughness;
+uniform float metalness;
+
+in FRAG_IN {
+    vec3 world_position;
+    vec3 world_normal;
+    vec3 clip_position;
+    float depth;
+} v_in;
+
+out vec4 frag_color;
+
+//@include 3D/lighting.glsl
+
+void main() {
+    frag_color = vec4(get_pbr_lit_color(albedo, roughness, metalness), 1.0);
+}
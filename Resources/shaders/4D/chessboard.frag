@@ -14,6 +14,7 @@ in FRAG_IN {
 out vec4 frag_color;
 
 //@include 4D/lighting.glsl
+//@include 4D/depth_cues.glsl
 
 void main() {
     vec4 p = v_in.world_position / square_width;
@@ -26,5 +27,5 @@ void main() {
     bool color_selector = mod_x ^^ mod_y ^^ mod_z ^^ mod_w;
 
     vec3 albedo = color_selector ? albedo_A : albedo_B;
-    frag_color = vec4(get_lit_color(albedo), 1.0);
+    frag_color = vec4(apply_depth_cues(get_lit_color(albedo)), 1.0);
 }
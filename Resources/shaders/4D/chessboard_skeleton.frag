@@ -14,6 +14,7 @@ in FRAG_IN {
 out vec4 frag_color;
 
 //@include 4D/lighting.glsl
+//@include 4D/depth_cues.glsl
 
 void main() {
     vec4 p = v_in.world_position / square_width;
@@ -26,7 +27,7 @@ void main() {
     bool color_selector = mod_x ^^ mod_y ^^ mod_z ^^ mod_w;
 
     vec3 albedo = color_selector ? albedo_A : albedo_B;
-    vec3 lit_color = get_lit_color(albedo);
+    vec3 lit_color = apply_depth_cues(get_lit_color(albedo));
 
     vec4 color_at_frustum_border = v_in.clip_position.x > 0.0 ? vec4(1.0, 0.0, 0.0, 0.0) : vec4(0.0, 0.0, 1.0, 0.0);
     float t = abs(v_in.clip_position.x / v_in.depth);
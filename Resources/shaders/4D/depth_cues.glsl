@@ -0,0 +1,29 @@
+//needs the fragment_uniforms block from 4D/lighting.glsl
+
+//signed distance of the fragment from the slice hyperplane
+float get_slice_distance() {
+    return dot(v_in.world_position - camera_position, slice_normal) - slice_offset;
+}
+
+vec3 apply_slice_tint(vec3 color) {
+    float slice_distance = get_slice_distance();
+    vec3 tint = slice_distance > 0.0 ? slice_tint_positive_color.xyz : slice_tint_negative_color.xyz;
+    float t = clamp(abs(slice_distance) / slice_tint_distance, 0.0, 1.0);
+    return mix(color, tint, t * slice_tint_strength);
+}
+
+vec3 apply_fog(vec3 color) {
+    //v_in.depth is the distance along the view direction
+    float t = clamp((v_in.depth - fog_start) / (fog_end - fog_start), 0.0, 1.0);
+    return mix(color, fog_color.xyz, t);
+}
+
+vec3 apply_depth_cues(vec3 color) {
+    if (slice_tint_enabled != 0) {
+        color = apply_slice_tint(color);
+    }
+    if (fog_enabled != 0) {
+        color = apply_fog(color);
+    }
+    return color;
+}
@@ -0,0 +1,21 @@
+#version 330 core
+
+uniform vec3 albedo;
+uniform float roughness;
+uniform float metalness;
+
+in FRAG_IN {
+    vec4 world_position;
+    vec4 world_normal;
+    vec4 clip_position;
+    float depth;
+} v_in;
+
+out vec4 frag_color;
+
+//@include 4D/lighting.glsl
+//@include 4D/depth_cues.glsl
+
+void main() {
+    frag_color = vec4(apply_depth_cues(get_pbr_lit_color(albedo, roughness, metalness)), 1.0);
+}
@@ -0,0 +1,26 @@
+#version 330 core
+
+//@include 4D/vertex_general.glsl
+
+out GS_IN {
+    vec4 world_position;
+    vec4 world_normal;
+    vec4 clip_position;
+    float depth;
+} v_out;
+
+void main() {
+    OutputData output_data = get_output_data();
+
+    v_out.world_position = output_data.world_position;
+    v_out.world_normal = output_data.world_normal;
+    v_out.clip_position = output_data.clip_position;
+    v_out.depth = output_data.depth;
+
+    //perspective division in 4D gives a point in the 3D volume, which is then rendered like any 3D point
+    vec3 volume_position = output_data.clip_position.xyz / output_data.depth;
+    gl_Position = vec4(
+        affine_transform(volume_to_clip_transform, volume_position),
+        affine_transform(volume_to_view_transform, volume_position).z
+    );
+}
@@ -12,7 +12,8 @@ in FRAG_IN {
 out vec4 frag_color;
 
 //@include 4D/lighting.glsl
+//@include 4D/depth_cues.glsl
 
 void main() {
-    frag_color = vec4(get_lit_color(albedo), 1.0);
+    frag_color = vec4(apply_depth_cues(get_lit_color(albedo)), 1.0);
 }
@@ -2,6 +2,10 @@ struct AffineTransform4D {
     mat4 matrix;
     vec4 translation;
 };
+struct AffineTransform3D {
+    mat3 matrix;
+    vec3 translation;
+};
 
 struct OutputData {
     vec4 world_position;
@@ -15,6 +19,9 @@ layout (std140) uniform vertex_uniforms {
     AffineTransform4D to_view_transform;
     AffineTransform4D to_clip_transform;
     mat4 normal_matrix;
+    //only used by projected.vert, for looking at the projected volume from outside
+    AffineTransform3D volume_to_view_transform;
+    AffineTransform3D volume_to_clip_transform;
 };
 
 in vec4 position;
@@ -23,6 +30,9 @@ in vec4 normal;
 vec4 affine_transform(AffineTransform4D transform, vec4 vector) {
     return transform.matrix * vector + transform.translation;
 }
+vec3 affine_transform(AffineTransform3D transform, vec3 vector) {
+    return transform.matrix * vector + transform.translation;
+}
 
 OutputData get_output_data() {
     vec4 world_position = affine_transform(to_world_transform, position);
@@ -0,0 +1,36 @@
+// Cook-Torrance with the GGX distribution. Only dot products are used, so it works with vectors of any dimension
+// (the normalization factor of the distribution is the 3D one, which is fine for shading).
+// References:
+// https://learnopengl.com/PBR/Theory
+
+const float PI = 3.14159265;
+
+float get_normal_distribution(float n_dot_h, float roughness) {
+    float a = roughness * roughness;
+    float a2 = a * a;
+    float d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+float get_geometry_term(float n_dot_x, float roughness) {
+    float k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+    return n_dot_x / (n_dot_x * (1.0 - k) + k);
+}
+
+vec3 get_fresnel(float h_dot_v, vec3 reflectance_at_normal) {
+    return reflectance_at_normal + (1.0 - reflectance_at_normal) * pow(clamp(1.0 - h_dot_v, 0.0, 1.0), 5.0);
+}
+
+// Fraction of the light color that is reflected towards the camera, including the cosine factor.
+// Scaled by PI, so that a rough dielectric is about as bright as the Lambert-only materials.
+vec3 get_reflected_fraction(float n_dot_l, float n_dot_v, float n_dot_h, float h_dot_v, vec3 albedo, float roughness, float metalness) {
+    vec3 reflectance_at_normal = mix(vec3(0.04), albedo, metalness);
+    vec3 fresnel = get_fresnel(h_dot_v, reflectance_at_normal);
+
+    float distribution = get_normal_distribution(n_dot_h, roughness);
+    float geometry = get_geometry_term(n_dot_v, roughness) * get_geometry_term(n_dot_l, roughness);
+    vec3 specular = distribution * geometry * fresnel / max(4.0 * n_dot_v * n_dot_l, 0.0001);
+
+    vec3 diffuse = (vec3(1.0) - fresnel) * (1.0 - metalness) * albedo;
+    return (diffuse + PI * specular) * n_dot_l;
+}
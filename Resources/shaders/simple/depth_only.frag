@@ -0,0 +1,7 @@
+#version 330 core
+
+uniform float depth_bias;//pushes the stored depth away from the light, to prevent surfaces from shadowing themselves
+
+void main() {
+    gl_FragDepth = gl_FragCoord.z + depth_bias;
+}
@@ -0,0 +1,12 @@
+#version 330 core
+
+in vec2 uv;
+out vec4 frag_color;
+
+uniform sampler2D texture_to_blit;
+uniform float opacity;
+
+void main() {
+    vec4 color = texture(texture_to_blit, uv);
+    frag_color = vec4(color.rgb, color.a * opacity);
+}
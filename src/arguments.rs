@@ -0,0 +1,161 @@
+use crate::global_data::{GlobalData, VisualMode};
+use crate::game::transform::Transform4D;
+use crate::game::transform::rotation::RotationPlane4D;
+use glam::{UVec2, Vec4, Mat4};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: game4D [options]
+    --help
+    --resolution <width>x<height>
+    --mode <1-7>                      visual mode, like the number keys
+    --seed <integer>                  for the random parts of the scene
+    --bookmark <name>                 start at a camera bookmark from Resources/bookmarks.json, like \"slot 1\"
+    --camera-path <file.json>         played with F10, instead of Resources/camera_path.json. With --headless, the frames follow it
+    --record-input <file.jsonl>       record the input of every frame, to reproduce bugs with --replay-input
+    --replay-input <file.jsonl>       play back recorded input instead of the keyboard and mouse, then continue normally
+    --headless <output.png>           render without a window and exit. Needs a display server, e.g. xvfb-run on CI
+    --position <x>,<y>,<z>,<w>        of the 4D camera, with --headless. Overrides --bookmark
+    --rotation <plane>:<degrees>,...  of the 4D camera, e.g. zw:30,xw:-10, applied from left to right, with --headless. Overrides --bookmark
+    --frames <count>                  numbers the output files if more than 1, with --headless
+    --frame-time <seconds>            simulated time between frames, with --headless";
+
+const DEFAULT_HEADLESS_FRAME_TIME: f32 = 1.0 / 60.0;
+
+pub struct Arguments {
+    pub resolution: Option<UVec2>,
+    pub visual_mode: Option<VisualMode>,
+    pub seed: Option<u64>,
+    pub bookmark: Option<String>,
+    pub camera_path: Option<PathBuf>,
+    pub record_input: Option<PathBuf>,
+    pub replay_input: Option<PathBuf>,
+    pub headless: Option<HeadlessArguments>
+}
+pub struct HeadlessArguments {
+    pub output_path: PathBuf,
+    pub camera_transform: Option<Transform4D>,
+    pub frame_count: u32,
+    pub frame_time: f32
+}
+
+impl Arguments {
+    pub fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            resolution: None,
+            visual_mode: None,
+            seed: None,
+            bookmark: None,
+            camera_path: None,
+            record_input: None,
+            replay_input: None,
+            headless: None
+        };
+        let mut output_path = None;
+        let mut camera_position = None;
+        let mut camera_orientation = None;
+        let mut frame_count = None;
+        let mut frame_time = None;
+
+        while let Some(argument) = arguments.next() {
+            let value = arguments.next().ok_or(format!("Missing value for {argument}"))?;
+            match argument.as_str() {
+                "--resolution"  => parsed.resolution = Some(parse_resolution(&value)?),
+                "--mode"        => parsed.visual_mode = Some(parse_visual_mode(&value)?),
+                "--seed"        => parsed.seed = Some(parse_number(&value)?),
+                "--bookmark"    => parsed.bookmark = Some(value),
+                "--camera-path" => parsed.camera_path = Some(PathBuf::from(value)),
+                "--record-input" => parsed.record_input = Some(PathBuf::from(value)),
+                "--replay-input" => parsed.replay_input = Some(PathBuf::from(value)),
+                "--headless"    => output_path = Some(PathBuf::from(value)),
+                "--position"    => camera_position = Some(parse_position(&value)?),
+                "--rotation"    => camera_orientation = Some(parse_rotation(&value)?),
+                "--frames"      => frame_count = Some(parse_number(&value)?),
+                "--frame-time"  => frame_time = Some(parse_number(&value)?),
+                _ => return Err(format!("Unknown argument {argument}"))
+            }
+        }
+
+        if output_path.is_some() && (parsed.record_input.is_some() || parsed.replay_input.is_some()) {
+            return Err("--record-input and --replay-input need a window, so they can't be used with --headless".to_string());
+        }
+
+        match output_path {
+            Some(output_path) => parsed.headless = Some(HeadlessArguments {
+                output_path,
+                camera_transform: match (camera_position, camera_orientation) {
+                    (None, None) => None,
+                    _ => Some(Transform4D {
+                        position: camera_position.unwrap_or(Vec4::Z),//eye height of a freshly spawned player
+                        orientation: camera_orientation.unwrap_or(Mat4::IDENTITY),
+                        ..Transform4D::default()
+                    })
+                },
+                frame_count: frame_count.unwrap_or(1),
+                frame_time: frame_time.unwrap_or(DEFAULT_HEADLESS_FRAME_TIME)
+            }),
+            None if camera_position.is_some() || camera_orientation.is_some() || frame_count.is_some() || frame_time.is_some() => {
+                return Err("--position, --rotation, --frames and --frame-time need --headless".to_string());
+            },
+            None => ()
+        }
+
+        Ok(parsed)
+    }
+
+    //overrides the options files
+    pub fn apply(&self, global_data: &mut GlobalData) {
+        if let Some(resolution) = self.resolution {
+            global_data.resolution = resolution;
+        }
+        if let Some(visual_mode) = self.visual_mode {
+            global_data.visual_mode = visual_mode;
+        }
+        if let Some(seed) = self.seed {
+            global_data.options.dev.scene.seed = Some(seed);
+        }
+        if let Some(camera_path) = &self.camera_path {
+            global_data.camera_path_file = camera_path.clone();
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.trim().parse().map_err(|_| format!("Not a valid number: {text}"))
+}
+
+fn parse_resolution(text: &str) -> Result<UVec2, String> {
+    let (width, height) = text.split_once('x').ok_or(format!("Expected <width>x<height>, got {text}"))?;
+    let resolution = UVec2::new(parse_number(width)?, parse_number(height)?);
+    match resolution.min_element() {
+        0 => Err(format!("Resolution can't be empty: {text}")),
+        _ => Ok(resolution)
+    }
+}
+
+fn parse_visual_mode(text: &str) -> Result<VisualMode, String> {
+    match parse_number(text)? {
+        int @ 1..=7 => Ok(VisualMode::from_int(int)),
+        _ => Err(format!("Unknown visual mode {text}"))
+    }
+}
+
+fn parse_position(text: &str) -> Result<Vec4, String> {
+    let components = text.split(',').map(parse_number).collect::<Result<Vec<f32>, String>>()?;
+    match components[..] {
+        [x, y, z, w] => Ok(Vec4::new(x, y, z, w)),
+        _ => Err(format!("Expected <x>,<y>,<z>,<w>, got {text}"))
+    }
+}
+
+fn parse_rotation(text: &str) -> Result<Mat4, String> {
+    let mut orientation = Mat4::IDENTITY;
+    for rotation in text.split(',') {
+        let (plane_name, degrees) = rotation.split_once(':').ok_or(format!("Expected <plane>:<degrees>, got {rotation}"))?;
+        let plane = RotationPlane4D::ALL.into_iter()
+            .find(|plane| format!("{plane:?}").eq_ignore_ascii_case(plane_name.trim()))
+            .ok_or(format!("Unknown rotation plane {plane_name}, expected one of xy, xz, xw, yz, yw, zw"))?;
+        orientation *= plane.rotation(parse_number::<f32>(degrees)?.to_radians());
+    }
+    Ok(orientation)
+}
@@ -1,4 +1,4 @@
-use std::time::{Instant, Duration};
+use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 
 const MEASUREMENT_INTERVAL_DURATION: Duration = Duration::from_millis(1000);
 
@@ -82,4 +82,34 @@ impl AverageFrameTimings {
             uncapped_milliseconds_per_frame: f32::NAN
         }
     }
-}
\ No newline at end of file
+}
+
+//UTC, like 2025-01-31_23-59-59-999, so that file names sort by time
+pub fn get_timestamp() -> String {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let second_of_day = seconds % 86400;
+
+    format!("{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}-{:03}",
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+//days since 1970-01-01 to a (year, month, day) date, see https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;//March is 0
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
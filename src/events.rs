@@ -1,11 +1,21 @@
 pub mod input;
+pub mod input_recording;
+pub mod actions;
+pub mod gamepad;
 
-use glium::glutin::{event::{self, VirtualKeyCode, ElementState}, window::CursorGrabMode};
+use glium::glutin::{event::{self, ElementState}, window::CursorGrabMode};
 use input::InputHandler;
-use crate::global_data::{GlobalData, VisualMode};
+use actions::{Action, Binding};
+use crate::global_data::GlobalData;
+use crate::game::bookmarks::BookmarkAction;
+use crate::game::camera_path::CameraPathAction;
+use crate::game::inspection::InspectionAction;
 use std::println;
 
-pub fn handle_event(event: event::Event<()>, input_handler: &mut InputHandler, global_data: &mut GlobalData, display: &glium::Display) {
+//for touchpads, which scroll by pixels instead of lines
+const PIXELS_PER_SCROLL_LINE: f32 = 20.0;
+
+pub fn handle_event(event: event::Event<()>, input_handler: &mut InputHandler, global_data: &mut GlobalData) {
     match event
     {
         event::Event::WindowEvent { event: win_event, .. } => match win_event
@@ -22,38 +32,25 @@ pub fn handle_event(event: event::Event<()>, input_handler: &mut InputHandler, g
                     },
                     _ => ()
                 }
-                match input
-                {
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F1), state: ElementState::Pressed, .. } => {
-                        global_data.reload_options();
-                        println!("Options reloaded");
-                    },
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F2), state: ElementState::Pressed, .. } => {
-                        set_mouse_grab(!global_data.mouse_grabbed, global_data, display);
-                    },
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F3), state: ElementState::Pressed, .. } => {
-                        global_data.info_screen_visible = !global_data.info_screen_visible;
-                    },
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::F4), state: ElementState::Pressed, .. } => {
-                        cycle_polygon_mode(global_data);
-                    },
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Key1), state: ElementState::Pressed, .. } => {
-                        global_data.visual_mode = VisualMode::from_int(1);
-                    },
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Key2), state: ElementState::Pressed, .. } => {
-                        global_data.visual_mode = VisualMode::from_int(2);
-                    },
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Key3), state: ElementState::Pressed, .. } => {
-                        global_data.visual_mode = VisualMode::from_int(3);
-                    },
-                    event::KeyboardInput { virtual_keycode: Some(VirtualKeyCode::Key4), state: ElementState::Pressed, .. } => {
-                        global_data.visual_mode = VisualMode::from_int(4);
+                if let event::KeyboardInput { virtual_keycode: Some(key), state: ElementState::Pressed, .. } = input {
+                    if let Some(action) = global_data.options.user.key_bindings.get_action(Binding::Key(key)) {
+                        input_handler.add_pressed_action(action);
                     }
-                    _ => ()
                 }
             },
             event::WindowEvent::MouseInput { button, state, .. } => {
                 input_handler.mouse_update_button(button, state);
+                if state == ElementState::Pressed {
+                    if let Some(action) = global_data.options.user.key_bindings.get_action(Binding::Mouse { mouse: button }) {
+                        input_handler.add_pressed_action(action);
+                    }
+                }
+            },
+            event::WindowEvent::MouseWheel { delta, .. } => {
+                input_handler.add_scroll_delta(match delta {
+                    event::MouseScrollDelta::LineDelta(_, y) => y,
+                    event::MouseScrollDelta::PixelDelta(position) => position.y as f32 / PIXELS_PER_SCROLL_LINE
+                });
             },
             event::WindowEvent::Resized(new_size) => {
                 global_data.resolution = glam::UVec2::new(new_size.width, new_size.height);
@@ -74,6 +71,67 @@ pub fn handle_event(event: event::Event<()>, input_handler: &mut InputHandler, g
     }
 }
 
+//while replaying recorded input, everything but closing the window comes from the recording
+pub fn handle_event_while_replaying(event: event::Event<()>, global_data: &mut GlobalData) {
+    if let event::Event::WindowEvent { event: win_event, .. } = event {
+        match win_event {
+            event::WindowEvent::CloseRequested => global_data.close_requested = true,
+            event::WindowEvent::Resized(new_size) => global_data.resolution = glam::UVec2::new(new_size.width, new_size.height),
+            _ => ()
+        }
+    }
+}
+
+//call once per frame, before handle_pressed_actions
+pub fn handle_gamepad_input(gamepad_input: &mut gamepad::GamepadInput, input_handler: &mut InputHandler, global_data: &GlobalData) {
+    for button in gamepad_input.update(input_handler) {
+        if let Some(action) = global_data.options.user.key_bindings.get_action(Binding::Gamepad { gamepad: button }) {
+            input_handler.add_pressed_action(action);
+        }
+    }
+}
+
+//call once per frame, before updating the game. Held actions are polled with KeyBindings::is_pressed instead
+pub fn handle_pressed_actions(input_handler: &InputHandler, global_data: &mut GlobalData, display: &glium::Display) {
+    for action in input_handler.pressed_actions() {
+        handle_action_pressed(*action, input_handler, global_data, display);
+    }
+}
+
+fn handle_action_pressed(action: Action, input_handler: &InputHandler, global_data: &mut GlobalData, display: &glium::Display) {
+    if let Some(visual_mode) = action.get_visual_mode() {
+        global_data.visual_mode = visual_mode;
+    }
+    if let Some(slot) = action.get_bookmark_slot() {
+        global_data.bookmark_action = Some(match global_data.options.user.key_bindings.is_pressed(Action::SaveBookmarkModifier, input_handler) {
+            true => BookmarkAction::Save(slot),
+            false => BookmarkAction::Recall(slot)
+        });
+    }
+
+    match action {
+        Action::ReloadOptions => {
+            global_data.reload_options();
+            println!("Options reloaded");
+        },
+        Action::ToggleMouseGrab => set_mouse_grab(!global_data.mouse_grabbed, global_data, display),
+        Action::ToggleInfoScreen => global_data.info_screen_visible = !global_data.info_screen_visible,
+        Action::CyclePolygonMode => cycle_polygon_mode(global_data),
+        Action::CycleCameraScheme => {
+            let options = &mut global_data.options.user.input;
+            options.camera_scheme_4D = options.camera_scheme_4D.next();
+            println!("4D camera controls: {:?}", options.camera_scheme_4D);
+        },
+        Action::Screenshot => global_data.screenshot_requested = true,
+        Action::ToggleRecording => global_data.recording_toggle_requested = true,
+        Action::ToggleCameraPath => global_data.camera_path_action = Some(CameraPathAction::TogglePlayback),
+        Action::PauseCameraPath => global_data.camera_path_action = Some(CameraPathAction::TogglePause),
+        Action::ToggleInspection => global_data.inspection_action = Some(InspectionAction::Toggle),
+        Action::InspectNextObject => global_data.inspection_action = Some(InspectionAction::NextObject),
+        _ => ()
+    }
+}
+
 pub fn set_mouse_grab(grabbed: bool, global_data: &mut GlobalData, display: &glium::Display) {
     let grab_mode = match grabbed {
         true => CursorGrabMode::Confined,//broken on Mac, iOS, Android and Web
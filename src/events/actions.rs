@@ -0,0 +1,229 @@
+use super::input::InputHandler;
+use crate::global_data::VisualMode;
+use glium::glutin::event::{VirtualKeyCode, MouseButton};
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    MoveAna,//along the 4D camera's x axis, which is perpendicular to the slice
+    MoveKata,
+    AlternativeLook,//while held, the mouse rotates the 4D camera in other planes
+    RollLook,//a third pair of planes, for the free camera schemes
+    CycleCameraScheme,
+    SliceOffsetForward,
+    SliceOffsetBackward,
+    ResetSliceOffset,
+    ReloadOptions,
+    ToggleMouseGrab,
+    ToggleInfoScreen,
+    CyclePolygonMode,
+    #[serde(rename = "set_mode_normal_3D")]
+    SetModeNormal3D,
+    #[serde(rename = "set_mode_combined_3D")]
+    SetModeCombined3D,
+    #[serde(rename = "set_mode_degenerate_3D")]
+    SetModeDegenerate3D,
+    #[serde(rename = "set_mode_degenerate_4D")]
+    SetModeDegenerate4D,
+    #[serde(rename = "set_mode_thick_slice_4D")]
+    SetModeThickSlice4D,
+    #[serde(rename = "set_mode_projection_4D")]
+    SetModeProjection4D,
+    #[serde(rename = "set_mode_combined_4D")]
+    SetModeCombined4D,
+    #[serde(rename = "bookmark_1")]
+    Bookmark1,
+    #[serde(rename = "bookmark_2")]
+    Bookmark2,
+    #[serde(rename = "bookmark_3")]
+    Bookmark3,
+    #[serde(rename = "bookmark_4")]
+    Bookmark4,
+    SaveBookmarkModifier,//while held, the bookmark actions save instead of recalling
+    ToggleRecording,
+    ToggleCameraPath,
+    PauseCameraPath,
+    ScrubCameraPathForward,
+    ScrubCameraPathBackward,
+    ToggleInspection,//orbits the object in the middle of the view
+    InspectNextObject,
+    Screenshot
+}
+impl Action {
+    pub const ALL: [Self; 38] = [
+        Self::MoveForward,
+        Self::MoveBackward,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveAna,
+        Self::MoveKata,
+        Self::AlternativeLook,
+        Self::RollLook,
+        Self::CycleCameraScheme,
+        Self::SliceOffsetForward,
+        Self::SliceOffsetBackward,
+        Self::ResetSliceOffset,
+        Self::ReloadOptions,
+        Self::ToggleMouseGrab,
+        Self::ToggleInfoScreen,
+        Self::CyclePolygonMode,
+        Self::SetModeNormal3D,
+        Self::SetModeCombined3D,
+        Self::SetModeDegenerate3D,
+        Self::SetModeDegenerate4D,
+        Self::SetModeThickSlice4D,
+        Self::SetModeProjection4D,
+        Self::SetModeCombined4D,
+        Self::Bookmark1,
+        Self::Bookmark2,
+        Self::Bookmark3,
+        Self::Bookmark4,
+        Self::SaveBookmarkModifier,
+        Self::ToggleRecording,
+        Self::ToggleCameraPath,
+        Self::PauseCameraPath,
+        Self::ScrubCameraPathForward,
+        Self::ScrubCameraPathBackward,
+        Self::ToggleInspection,
+        Self::InspectNextObject,
+        Self::Screenshot
+    ];
+
+    pub fn get_visual_mode(&self) -> Option<VisualMode> {
+        match self {
+            Self::SetModeNormal3D     => Some(VisualMode::Normal3D),
+            Self::SetModeCombined3D   => Some(VisualMode::Combined3D),
+            Self::SetModeDegenerate3D => Some(VisualMode::Degenerate3D),
+            Self::SetModeDegenerate4D => Some(VisualMode::Degenerate4D),
+            Self::SetModeThickSlice4D => Some(VisualMode::ThickSlice4D),
+            Self::SetModeProjection4D => Some(VisualMode::Projection4D),
+            Self::SetModeCombined4D   => Some(VisualMode::Combined4D),
+            _ => None
+        }
+    }
+
+    pub fn get_bookmark_slot(&self) -> Option<u32> {
+        match self {
+            Self::Bookmark1 => Some(1),
+            Self::Bookmark2 => Some(2),
+            Self::Bookmark3 => Some(3),
+            Self::Bookmark4 => Some(4),
+            _ => None
+        }
+    }
+}
+
+//in options.json, keys are written like "W", "Space" or "F1", mouse buttons like {"mouse": "Left"} and gamepad buttons like {"gamepad": "South"}
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged, try_from = "BindingName")]
+pub enum Binding {
+    Key(VirtualKeyCode),
+    Mouse { mouse: MouseButton },
+    Gamepad { gamepad: gilrs::Button }
+}
+
+//parsed in two steps, since serde can't tell which variant of an untagged enum failed
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BindingName {
+    Key(String),
+    Mouse { mouse: String },
+    Gamepad { gamepad: String }
+}
+impl TryFrom<BindingName> for Binding {
+    type Error = String;
+
+    fn try_from(name: BindingName) -> Result<Self, String> {
+        match name {
+            BindingName::Key(key) => serde_json::from_value(key.clone().into())
+                .map(Self::Key)
+                .map_err(|_| format!("Unknown key '{key}', see the names at https://docs.rs/winit/0.27.5/winit/event/enum.VirtualKeyCode.html")),
+            BindingName::Mouse { mouse } => serde_json::from_value(mouse.clone().into())
+                .map(|mouse| Self::Mouse { mouse })
+                .map_err(|_| format!("Unknown mouse button '{mouse}', expected Left, Right or Middle")),
+            BindingName::Gamepad { gamepad } => serde_json::from_value(gamepad.clone().into())
+                .map(|gamepad| Self::Gamepad { gamepad })
+                .map_err(|_| format!("Unknown gamepad button '{gamepad}', see the names at https://docs.rs/gilrs/0.11.0/gilrs/ev/enum.Button.html"))
+        }
+    }
+}
+impl fmt::Display for Binding {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(key) => write!(formatter, "key {key:?}"),
+            Self::Mouse { mouse } => write!(formatter, "mouse button {mouse:?}"),
+            Self::Gamepad { gamepad } => write!(formatter, "gamepad button {gamepad:?}")
+        }
+    }
+}
+
+//every action has to be listed, but may have no bindings. A key can only trigger one action
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(try_from = "BTreeMap<Action, Vec<Binding>>", into = "BTreeMap<Action, Vec<Binding>>")]
+pub struct KeyBindings {
+    bindings: BTreeMap<Action, Vec<Binding>>,
+    actions: HashMap<Binding, Action>
+}
+impl KeyBindings {
+    pub fn is_pressed(&self, action: Action, input: &InputHandler) -> bool {
+        self.bindings[&action].iter().any(|binding| match binding {
+            Binding::Key(key) => input.keyboard_is_pressed(key),
+            Binding::Mouse { mouse } => input.mouse_is_pressed(mouse),
+            Binding::Gamepad { gamepad } => input.gamepad_is_pressed(gamepad)
+        })
+    }
+
+    pub fn get_action(&self, binding: Binding) -> Option<Action> {
+        self.actions.get(&binding).copied()
+    }
+}
+
+impl TryFrom<BTreeMap<Action, Vec<Binding>>> for KeyBindings {
+    type Error = String;
+
+    fn try_from(bindings: BTreeMap<Action, Vec<Binding>>) -> Result<Self, String> {
+        let missing_actions: Vec<_> = Action::ALL.iter()
+            .filter(|action| !bindings.contains_key(action))
+            .map(|action| get_action_name(*action))
+            .collect();
+        if !missing_actions.is_empty() {
+            return Err(format!("key_bindings is missing {} (use [] for no bindings)", missing_actions.join(", ")));
+        }
+
+        let mut actions = HashMap::new();
+        for (action, action_bindings) in &bindings {
+            for binding in action_bindings {
+                if let Some(other_action) = actions.insert(*binding, *action) {
+                    return Err(match other_action == *action {
+                        true => format!("The {binding} is listed twice for {}", get_action_name(*action)),
+                        false => format!("The {binding} is bound to both {} and {}", get_action_name(other_action), get_action_name(*action))
+                    });
+                }
+            }
+        }
+
+        Ok(Self { bindings, actions })
+    }
+}
+
+impl From<KeyBindings> for BTreeMap<Action, Vec<Binding>> {
+    fn from(key_bindings: KeyBindings) -> Self {
+        key_bindings.bindings
+    }
+}
+
+//as written in options.json
+fn get_action_name(action: Action) -> String {
+    serde_json::to_value(action).unwrap().as_str().unwrap().to_string()
+}
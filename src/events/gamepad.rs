@@ -0,0 +1,81 @@
+use super::input::{InputHandler, GamepadAxes};
+use gilrs::{Gilrs, GamepadId, EventType, Axis, Button};
+
+//feeds the gamepad that was used last into the InputHandler
+pub struct GamepadInput {
+    gilrs: Option<Gilrs>,//None if the platform has no gamepad support
+    active_gamepad: Option<GamepadId>
+}
+impl Default for GamepadInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl GamepadInput {
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(error) => {
+                eprintln!("Gamepads are not available: {error}");
+                None
+            }
+        };
+
+        Self {
+            gilrs,
+            active_gamepad: None
+        }
+    }
+
+    //returns the buttons pressed since the last call, for the actions that happen once per press
+    pub fn update(&mut self, input_handler: &mut InputHandler) -> Vec<Button> {
+        let Some(gilrs) = &mut self.gilrs else {
+            return Vec::new();
+        };
+
+        let mut pressed_buttons = Vec::new();
+        while let Some(event) = gilrs.next_event() {
+            if self.active_gamepad != Some(event.id) {
+                match event.event {
+                    EventType::ButtonPressed(..) | EventType::AxisChanged(..) => {
+                        self.active_gamepad = Some(event.id);
+                        input_handler.release_gamepad();
+                    },
+                    _ => continue
+                }
+            }
+
+            match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    input_handler.gamepad_update_button(button, true);
+                    pressed_buttons.push(button);
+                },
+                EventType::ButtonReleased(button, _) => input_handler.gamepad_update_button(button, false),
+                EventType::Disconnected => {
+                    self.active_gamepad = None;
+                    input_handler.release_gamepad();
+                },
+                _ => ()
+            }
+        }
+
+        if let Some(gamepad) = self.active_gamepad.and_then(|id| gilrs.connected_gamepad(id)) {
+            let trigger_value = |button| gamepad.button_data(button).map_or(0.0, |data| data.value());
+            input_handler.set_gamepad_axes(GamepadAxes {
+                left_stick: glam::Vec2::new(gamepad.value(Axis::LeftStickX), gamepad.value(Axis::LeftStickY)),
+                right_stick: glam::Vec2::new(gamepad.value(Axis::RightStickX), gamepad.value(Axis::RightStickY)),
+                left_trigger: trigger_value(Button::LeftTrigger2),
+                right_trigger: trigger_value(Button::RightTrigger2)
+            });
+        }
+
+        pressed_buttons
+    }
+
+    //drops the events without feeding them into an InputHandler, e.g. while replaying recorded input
+    pub fn discard_events(&mut self) {
+        if let Some(gilrs) = &mut self.gilrs {
+            while gilrs.next_event().is_some() {}
+        }
+    }
+}
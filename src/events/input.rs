@@ -1,18 +1,67 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use glium::glutin::event::{VirtualKeyCode, MouseButton, ElementState};
 use glam::{Vec2, DVec2};
+use serde::{Serialize, Deserialize};
+use super::actions::Action;
+
+//everything update_game reads from an InputHandler
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputState {
+    pub pressed_keys: Vec<VirtualKeyCode>,
+    pub pressed_mouse_buttons: Vec<MouseButton>,
+    #[serde(default)]//missing from recordings made before gamepad support
+    pub pressed_gamepad_buttons: Vec<gilrs::Button>,
+    pub mouse_delta: DVec2,
+    pub scroll_delta: f32,
+    #[serde(default)]
+    pub gamepad_axes: GamepadAxes
+}
+
+//of the active gamepad, without deadzones. Stick y is up, triggers go from 0 to 1
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct GamepadAxes {
+    pub left_stick: Vec2,
+    pub right_stick: Vec2,
+    pub left_trigger: f32,
+    pub right_trigger: f32
+}
+impl GamepadAxes {
+    //rescaled, so that the values still start from 0 at the edge of the deadzone
+    pub fn with_deadzone(&self, deadzone: f32) -> Self {
+        let apply = |length: f32| ((length - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0);
+        let apply_to_stick = |stick: Vec2| {
+            let length = stick.length();
+            if length > 0.0 { stick * apply(length) / length } else { Vec2::ZERO }
+        };
+
+        Self {
+            left_stick: apply_to_stick(self.left_stick),
+            right_stick: apply_to_stick(self.right_stick),
+            left_trigger: apply(self.left_trigger),
+            right_trigger: apply(self.right_trigger)
+        }
+    }
+}
 
 pub struct InputHandler {
     keyboard_key_map: HashMap<VirtualKeyCode, ElementState>,
     mouse_button_map: HashMap<MouseButton, ElementState>,
-    mouse_delta: DVec2
+    pressed_gamepad_buttons: HashSet<gilrs::Button>,
+    mouse_delta: DVec2,
+    scroll_delta: f32,//in lines
+    gamepad_axes: GamepadAxes,
+    pressed_actions: Vec<Action>//since the last frame, for the actions that happen once per press
 }
 impl InputHandler {
     pub fn new() -> Self {
         Self {
             keyboard_key_map: HashMap::new(),
             mouse_button_map: HashMap::new(),
-            mouse_delta: DVec2::ZERO
+            pressed_gamepad_buttons: HashSet::new(),
+            mouse_delta: DVec2::ZERO,
+            scroll_delta: 0.0,
+            gamepad_axes: GamepadAxes::default(),
+            pressed_actions: Vec::new()
         }
     }
 
@@ -32,6 +81,10 @@ impl InputHandler {
         }
     }
 
+    pub fn gamepad_is_pressed(&self, button: &gilrs::Button) -> bool {
+        self.pressed_gamepad_buttons.contains(button)
+    }
+
     pub fn keyboard_is_released(&self, key: &VirtualKeyCode) -> bool {
         !self.keyboard_is_pressed(key)
     }
@@ -48,6 +101,27 @@ impl InputHandler {
         self.mouse_button_map.insert(button, state);
     }
 
+    pub fn gamepad_update_button(&mut self, button: gilrs::Button, is_pressed: bool) {
+        match is_pressed {
+            true => self.pressed_gamepad_buttons.insert(button),
+            false => self.pressed_gamepad_buttons.remove(&button)
+        };
+    }
+
+    //so that nothing stays held when switching gamepads
+    pub fn release_gamepad(&mut self) {
+        self.pressed_gamepad_buttons.clear();
+        self.gamepad_axes = GamepadAxes::default();
+    }
+
+    pub fn gamepad_axes(&self) -> GamepadAxes {
+        self.gamepad_axes
+    }
+
+    pub fn set_gamepad_axes(&mut self, axes: GamepadAxes) {
+        self.gamepad_axes = axes;
+    }
+
     pub fn mouse_delta(&self) -> Vec2 {
         self.mouse_delta.as_vec2()
     }
@@ -56,7 +130,60 @@ impl InputHandler {
         self.mouse_delta += DVec2::new(x_delta, y_delta);
     }
 
+    pub fn scroll_delta(&self) -> f32 {
+        self.scroll_delta
+    }
+
+    pub fn add_scroll_delta(&mut self, delta: f32) {
+        self.scroll_delta += delta;
+    }
+
+    pub fn pressed_actions(&self) -> &[Action] {
+        &self.pressed_actions
+    }
+
+    pub fn add_pressed_action(&mut self, action: Action) {
+        self.pressed_actions.push(action);
+    }
+
+    pub fn set_pressed_actions(&mut self, actions: Vec<Action>) {
+        self.pressed_actions = actions;
+    }
+
+    //the pressed actions are cleared too, since they are handled once per frame
     pub fn reset_deltas(&mut self) {
         self.mouse_delta = DVec2::ZERO;
+        self.scroll_delta = 0.0;
+        self.pressed_actions.clear();
+    }
+
+    pub fn get_state(&self) -> InputState {
+        let mut pressed_keys: Vec<_> = self.keyboard_key_map.iter()
+            .filter(|(_, state)| **state == ElementState::Pressed)
+            .map(|(key, _)| *key)
+            .collect();
+        pressed_keys.sort();//for readable files, the order doesn't matter otherwise
+
+        InputState {
+            pressed_keys,
+            pressed_mouse_buttons: self.mouse_button_map.iter()
+                .filter(|(_, state)| **state == ElementState::Pressed)
+                .map(|(button, _)| *button)
+                .collect(),
+            pressed_gamepad_buttons: self.pressed_gamepad_buttons.iter().copied().collect(),
+            mouse_delta: self.mouse_delta,
+            scroll_delta: self.scroll_delta,
+            gamepad_axes: self.gamepad_axes
+        }
+    }
+
+    //replaces everything, including the deltas
+    pub fn set_state(&mut self, state: &InputState) {
+        self.keyboard_key_map = state.pressed_keys.iter().map(|key| (*key, ElementState::Pressed)).collect();
+        self.mouse_button_map = state.pressed_mouse_buttons.iter().map(|button| (*button, ElementState::Pressed)).collect();
+        self.pressed_gamepad_buttons = state.pressed_gamepad_buttons.iter().copied().collect();
+        self.mouse_delta = state.mouse_delta;
+        self.scroll_delta = state.scroll_delta;
+        self.gamepad_axes = state.gamepad_axes;
     }
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+use super::input::{InputHandler, InputState};
+use crate::global_data::{GlobalData, VisualMode};
+use crate::game::world::Multiverse;
+use crate::game::bookmarks::{self, CameraBookmark};
+use crate::game::camera_path::{self, CameraPathFile};
+use crate::options::{InputOptions, PlayerOptions};
+use super::actions::{Action, KeyBindings};
+use serde::{Serialize, Deserialize};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::collections::BTreeMap;
+
+/* An input recording is a JSON object per line: a header, then a frame per update_game call.
+   The header has everything player movement depends on apart from the input, so the options can
+   change between recording and replaying. Reloading the options (F1) while recording isn't recorded,
+   since it would replace the recorded options when replaying. */
+#[derive(Serialize, Deserialize)]
+struct InputRecordingHeader {
+    seed: u64,
+    start: CameraBookmark,
+    player_options: PlayerOptions,
+    input_options: InputOptions,
+    key_bindings: KeyBindings,
+    #[serde(default)]//missing from older recordings
+    files: RecordedFiles
+}
+
+//the files that one-shot actions read, as they were when recording started. Replays use these instead, and don't write the files
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct RecordedFiles {
+    pub bookmarks: BTreeMap<String, CameraBookmark>,
+    pub camera_path: Option<CameraPathFile>//None if it couldn't be loaded
+}
+impl RecordedFiles {
+    fn load(global_data: &GlobalData) -> Self {
+        Self {
+            bookmarks: bookmarks::load_bookmarks().unwrap_or_else(|message| {
+                eprintln!("{message}");
+                BTreeMap::new()
+            }),
+            camera_path: camera_path::load_camera_path_file(&global_data.camera_path_file).ok()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct InputFrame {
+    delta_time: f32,
+    visual_mode: VisualMode,//switched by hotkeys, which aren't part of InputState
+    input: InputState,
+    #[serde(default)]//missing from older recordings
+    actions: Vec<Action>//the ones that happen once per press, like bookmarks and inspection
+}
+
+//call before creating the Multiverse, since the scene needs a seed to be reproducible
+pub fn choose_seed(global_data: &mut GlobalData) {
+    if global_data.options.dev.scene.seed.is_none() {
+        global_data.options.dev.scene.seed = Some(rand::random());
+    }
+}
+
+pub struct InputRecorder {
+    file: BufWriter<File>
+}
+impl InputRecorder {
+    pub fn new(path: &Path, multiverse: &Multiverse, global_data: &GlobalData) -> io::Result<Self> {
+        let header = InputRecordingHeader {
+            seed: global_data.options.dev.scene.seed.expect("choose_seed wasn't called"),
+            start: CameraBookmark {
+                player_3D: multiverse.world_3D.player.get_bookmark(),
+                player_4D: multiverse.world_4D.player.get_bookmark()
+            },
+            player_options: global_data.options.dev.player.clone(),
+            input_options: global_data.options.user.input.clone(),
+            key_bindings: global_data.options.user.key_bindings.clone(),
+            files: RecordedFiles::load(global_data)
+        };
+
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+        Ok(Self { file })
+    }
+
+    //flushed every frame, so that a crash doesn't lose the input that caused it
+    pub fn record_frame(&mut self, input: &InputHandler, delta_time: f32, global_data: &GlobalData) -> io::Result<()> {
+        let frame = InputFrame {
+            delta_time,
+            visual_mode: global_data.visual_mode,
+            input: input.get_state(),
+            actions: input.pressed_actions().iter()
+                .copied()
+                .filter(|action| *action != Action::ReloadOptions)
+                .collect()
+        };
+        writeln!(self.file, "{}", serde_json::to_string(&frame)?)?;
+        self.file.flush()
+    }
+}
+
+pub struct InputReplay {
+    header: InputRecordingHeader,
+    frames: std::vec::IntoIter<InputFrame>
+}
+impl InputReplay {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+        let mut lines = text.lines().enumerate();
+        let parse_error = |line_index: usize, error: serde_json::Error| format!("Failed to parse {}, line {}: {error}", path.display(), line_index + 1);
+
+        let (_, header_line) = lines.next().ok_or(format!("{} is empty", path.display()))?;
+        let header = serde_json::from_str(header_line).map_err(|error| parse_error(0, error))?;
+        let frames = lines
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(line_index, line)| serde_json::from_str(line).map_err(|error| parse_error(line_index, error)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            header,
+            frames: frames.into_iter()
+        })
+    }
+
+    //call before creating the Multiverse
+    pub fn apply_options(&self, global_data: &mut GlobalData) {
+        global_data.options.dev.scene.seed = Some(self.header.seed);
+        global_data.options.dev.player = self.header.player_options.clone();
+        global_data.options.user.input = self.header.input_options.clone();
+        global_data.options.user.key_bindings = self.header.key_bindings.clone();
+        global_data.replayed_files = Some(self.header.files.clone());
+    }
+
+    pub fn place_players(&self, multiverse: &mut Multiverse) {
+        multiverse.world_3D.player.go_to_bookmark(&self.header.start.player_3D);
+        multiverse.world_4D.player.go_to_bookmark(&self.header.start.player_4D);
+    }
+
+    //overwrites the live input and pressed actions, returns the recorded delta time, or None when the replay is over
+    pub fn next_frame(&mut self, input: &mut InputHandler, global_data: &mut GlobalData) -> Option<f32> {
+        let frame = self.frames.next()?;
+        input.set_state(&frame.input);
+        input.set_pressed_actions(frame.actions);
+        global_data.visual_mode = frame.visual_mode;
+        Some(frame.delta_time)
+    }
+}
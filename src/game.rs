@@ -1,27 +1,76 @@
 pub mod world;
 pub mod transform;
 pub mod player;
+pub mod entity;
+pub mod scene;
+pub mod animation;
+pub mod light;
+pub mod bookmarks;
+pub mod camera_path;
+pub mod inspection;
+pub mod picking;
+pub mod bounds;
+pub mod bvh;
 
 use world::Multiverse;
 use crate::events::input::InputHandler;
 use crate::global_data::GlobalData;
+use crate::events::actions::Action;
 
-pub fn update_game(multiverse: &mut Multiverse, input: &InputHandler, global_data: &mut GlobalData) {
+//delta_time is from get_delta_time, unless replaying recorded input
+pub fn update_game(multiverse: &mut Multiverse, input: &InputHandler, global_data: &mut GlobalData, delta_time: f32) {
 
-    let delta_time = get_delta_time(multiverse);
+    if let Some(action) = global_data.bookmark_action.take() {
+        bookmarks::do_bookmark_action(action, multiverse, global_data);
+    }
+    if let Some(action) = global_data.camera_path_action.take() {
+        camera_path::do_camera_path_action(action, multiverse, global_data);
+    }
+    if let Some(action) = global_data.inspection_action.take() {
+        inspection::do_inspection_action(action, multiverse, global_data);
+    }
+
+    let is_inspecting = global_data.is_4D_active() && multiverse.inspection.is_some();
+    update_slice_offset(delta_time, input, !is_inspecting, global_data);
 
     if global_data.is_4D_active() {
-        multiverse.world_4D.player.update(delta_time, input, global_data)
+        match (&mut multiverse.camera_path, &mut multiverse.inspection) {
+            (Some(playback), _) => camera_path::update_camera_path(playback, &mut multiverse.world_4D.player, delta_time, input, global_data),
+            (None, Some(inspection)) => {
+                if !inspection::update_inspection(inspection, &mut multiverse.world_4D, delta_time, input, global_data) {
+                    println!("The inspected object is gone");
+                    global_data.inspection_action = Some(inspection::InspectionAction::Toggle);
+                }
+            },
+            (None, None) => multiverse.world_4D.player.update(delta_time, input, global_data)
+        }
+        multiverse.world_4D.update_scene(delta_time);
     }
     else {
         multiverse.world_3D.player.update(delta_time, input, global_data);
+        multiverse.world_3D.scene.update(delta_time);
+    }
+}
+
+//scans through objects, without moving the camera. Scrolling is used for zooming while inspecting
+fn update_slice_offset(delta_time: f32, input: &InputHandler, use_scroll: bool, global_data: &mut GlobalData) {
+    let options = &global_data.options.user.input;
+    let bindings = &global_data.options.user.key_bindings;
+    let mut direction = 0.0;
+    if bindings.is_pressed(Action::SliceOffsetForward, input)  { direction += 1.0 };
+    if bindings.is_pressed(Action::SliceOffsetBackward, input) { direction -= 1.0 };
+
+    global_data.slice_offset += direction * options.slice_offset_speed * delta_time
+        + if use_scroll { input.scroll_delta() * options.slice_offset_scroll_step } else { 0.0 };
+    if bindings.is_pressed(Action::ResetSliceOffset, input) {
+        global_data.slice_offset = 0.0;
     }
 }
 
-pub fn get_delta_time(multiverse: &mut Multiverse) -> f32 {
+pub fn get_delta_time(multiverse: &mut Multiverse, global_data: &GlobalData) -> f32 {
     let now = std::time::Instant::now();
     let delta_time = (now - multiverse.last_update_time).as_secs_f32();
     multiverse.last_update_time = now;
 
-    delta_time
+    global_data.fixed_delta_time.unwrap_or(delta_time)
 }
\ No newline at end of file
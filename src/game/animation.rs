@@ -0,0 +1,152 @@
+use super::transform::Transform4D;
+use super::transform::rotation::RotationPlane4D;
+use super::transform::rotor::{Rotor4D, Bivector4D};
+use glam::Vec4;
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackMode {
+    Once,//stops at the last keyframe
+    Loop,
+    PingPong//plays forward, then backward
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    Step,
+    Linear,
+    Smooth//ease in and out of every keyframe
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Keyframe4D {
+    pub time: f32,
+    pub position: Vec4,
+    pub orientation: Rotor4D,
+    pub scale: Vec4
+}
+
+//radians, in the order of RotationPlane4D::ALL
+pub fn get_rotor_from_angles(angles: [f32; 6]) -> Rotor4D {
+    RotationPlane4D::ALL.iter()
+        .zip(angles)
+        .map(|(plane, angle)| Rotor4D::from_plane(*plane, angle))
+        .fold(Rotor4D::IDENTITY, |a, b| a * b)
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct AngularVelocity4D {
+    pub plane: RotationPlane4D,
+    pub radians_per_second: f32
+}
+
+/* Drives the transform of an entity. The keyframe path (if any) gives the position, orientation and scale,
+   and the spin is then applied on top of the orientation. Without keyframes, base_transform is used instead.
+   Orientations are slerped between keyframes, and all spins are summed into a single uniform rotation. */
+#[derive(Debug, Clone)]
+pub struct Animation4D {
+    pub base_transform: Transform4D,
+    pub spin: Vec<AngularVelocity4D>,
+    pub keyframes: Vec<Keyframe4D>,//sorted by time
+    pub interpolation: Interpolation,
+    pub playback_mode: PlaybackMode,
+    pub time: f32
+}
+impl Animation4D {
+    pub fn spinning(base_transform: Transform4D, spin: Vec<AngularVelocity4D>) -> Self {
+        Self {
+            base_transform,
+            spin,
+            keyframes: Vec::new(),
+            interpolation: Interpolation::Linear,
+            playback_mode: PlaybackMode::Loop,
+            time: 0.0
+        }
+    }
+
+    pub fn keyframed(keyframes: Vec<Keyframe4D>, interpolation: Interpolation, playback_mode: PlaybackMode) -> Self {
+        Self {
+            base_transform: Transform4D::IDENTITY,
+            spin: Vec::new(),
+            keyframes,
+            interpolation,
+            playback_mode,
+            time: 0.0
+        }
+    }
+
+    pub fn advance(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    pub fn get_transform(&self) -> Transform4D {
+        let mut transform = match self.get_path_transform() {
+            Some(path_transform) => path_transform,
+            None => self.base_transform
+        };
+
+        let angular_velocity = self.spin.iter()
+            .map(|spin| Bivector4D::from_plane(spin.plane, spin.radians_per_second))
+            .fold(Bivector4D::ZERO, |a, b| a + b);
+        transform.orientation *= (angular_velocity * self.time).exp().to_mat4();
+
+        transform
+    }
+
+    pub fn duration(&self) -> f32 {
+        match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0
+        }
+    }
+
+    fn get_path_transform(&self) -> Option<Transform4D> {
+        let first = self.keyframes.first()?;
+        let path_time = first.time + self.get_playback_time();
+
+        //index of the first keyframe after path_time
+        let next_index = self.keyframes.partition_point(|keyframe| keyframe.time <= path_time);
+        if next_index == 0 || next_index == self.keyframes.len() {
+            let keyframe = self.keyframes[next_index.min(self.keyframes.len() - 1)];
+            return Some(Transform4D {
+                position: keyframe.position,
+                orientation: keyframe.orientation.to_mat4(),
+                scale: keyframe.scale
+            });
+        }
+
+        let previous = self.keyframes[next_index - 1];
+        let next = self.keyframes[next_index];
+        let t = (path_time - previous.time) / (next.time - previous.time);
+        let t = match self.interpolation {
+            Interpolation::Step => 0.0,
+            Interpolation::Linear => t,
+            Interpolation::Smooth => t * t * (3.0 - 2.0 * t)
+        };
+
+        Some(Transform4D {
+            position: previous.position.lerp(next.position, t),
+            orientation: previous.orientation.slerp(next.orientation, t).to_mat4(),
+            scale: previous.scale.lerp(next.scale, t)
+        })
+    }
+
+    //time relative to the first keyframe
+    pub fn get_playback_time(&self) -> f32 {
+        let duration = self.duration();
+        if duration <= 0.0 {
+            return 0.0;
+        }
+
+        match self.playback_mode {
+            PlaybackMode::Once => self.time.clamp(0.0, duration),
+            PlaybackMode::Loop => self.time.rem_euclid(duration),
+            PlaybackMode::PingPong => {
+                let t = self.time.rem_euclid(2.0 * duration);
+                if t < duration { t } else { 2.0 * duration - t }
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use super::world::Multiverse;
+use crate::global_data::GlobalData;
+use super::player::{PlayerBookmark3D, PlayerBookmark4D};
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+//shared by everyone working on the same checkout, so that views can be discussed by name
+const BOOKMARK_FILE: &str = "Resources/bookmarks.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct CameraBookmark {
+    pub player_3D: PlayerBookmark3D,
+    pub player_4D: PlayerBookmark4D
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BookmarkAction {
+    Save(u32),
+    Recall(u32)
+}
+
+pub fn get_slot_name(slot: u32) -> String {
+    format!("slot {slot}")
+}
+
+//a missing file has no bookmarks
+pub fn load_bookmarks() -> Result<BTreeMap<String, CameraBookmark>, String> {
+    match fs::read_to_string(BOOKMARK_FILE) {
+        Ok(json) => serde_json::from_str(&json).map_err(|error| format!("Failed to parse {BOOKMARK_FILE}: {error}")),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(error) => Err(format!("Failed to read {BOOKMARK_FILE}: {error}"))
+    }
+}
+
+//while replaying recorded input, only the recorded copy is changed
+pub fn save_bookmark(name: &str, multiverse: &Multiverse, global_data: &mut GlobalData) -> Result<(), String> {
+    let bookmark = CameraBookmark {
+        player_3D: multiverse.world_3D.player.get_bookmark(),
+        player_4D: multiverse.world_4D.player.get_bookmark()
+    };
+    if let Some(files) = &mut global_data.replayed_files {
+        files.bookmarks.insert(name.to_string(), bookmark);
+        return Ok(());
+    }
+
+    let mut bookmarks = load_bookmarks()?;
+    bookmarks.insert(name.to_string(), bookmark);
+    let json = serde_json::to_string_pretty(&bookmarks).unwrap();
+    fs::write(BOOKMARK_FILE, json).map_err(|error| format!("Failed to write {BOOKMARK_FILE}: {error}"))
+}
+
+//the camera scheme in the options follows the bookmark, so that the next update keeps the view
+pub fn go_to_bookmark(name: &str, multiverse: &mut Multiverse, global_data: &mut GlobalData) -> Result<(), String> {
+    let (bookmarks, source) = match &global_data.replayed_files {
+        Some(files) => (files.bookmarks.clone(), "the input recording"),
+        None => (load_bookmarks()?, BOOKMARK_FILE)
+    };
+    let bookmark = bookmarks.get(name).ok_or_else(|| format!(
+        "No bookmark named '{name}' in {source}, the available ones are: {}",
+        bookmarks.keys().map(|name| format!("'{name}'")).collect::<Vec<_>>().join(", ")
+    ))?;
+
+    multiverse.world_3D.player.go_to_bookmark(&bookmark.player_3D);
+    multiverse.world_4D.player.go_to_bookmark(&bookmark.player_4D);
+    global_data.options.user.input.camera_scheme_4D = bookmark.player_4D.camera_scheme;
+    Ok(())
+}
+
+pub fn do_bookmark_action(action: BookmarkAction, multiverse: &mut Multiverse, global_data: &mut GlobalData) {
+    let result = match action {
+        BookmarkAction::Save(slot) => save_bookmark(&get_slot_name(slot), multiverse, global_data)
+            .map(|()| format!("Bookmark '{}' saved", get_slot_name(slot))),
+        BookmarkAction::Recall(slot) => go_to_bookmark(&get_slot_name(slot), multiverse, global_data)
+            .map(|()| format!("Bookmark '{}' recalled", get_slot_name(slot)))
+    };
+
+    match result {
+        Ok(message) => println!("{message}"),
+        Err(message) => eprintln!("{message}")
+    }
+}
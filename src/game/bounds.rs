@@ -0,0 +1,232 @@
+use super::transform::AffineTransform4D;
+use super::picking::Ray4D;
+use crate::renderer::mesh::Mesh4D;
+use glam::{Vec3, Mat3, Vec4, Mat4};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb4D {
+    pub min: Vec4,
+    pub max: Vec4
+}
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingHypersphere4D {
+    pub center: Vec4,
+    pub radius: f32
+}
+//the points p with normal.dot(p) == distance. The normal is normalized
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hyperplane4D {
+    pub normal: Vec4,
+    pub distance: f32
+}
+
+impl Aabb4D {
+    //contains nothing, and leaves other boxes unchanged in a union
+    pub const EMPTY: Self = Self {
+        min: Vec4::INFINITY,
+        max: Vec4::NEG_INFINITY
+    };
+
+    pub fn from_points<I: IntoIterator<Item = Vec4>>(points: I) -> Self {
+        points.into_iter().fold(Self::EMPTY, |aabb, point| aabb.union(&Self { min: point, max: point }))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    pub fn center(&self) -> Vec4 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn half_extents(&self) -> Vec4 {
+        0.5 * (self.max - self.min)
+    }
+
+    pub fn contains_point(&self, point: Vec4) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    //bounds the transformed box, which can be bigger than the box around the transformed contents
+    pub fn transformed(&self, transform: &AffineTransform4D) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let linear = transform.linear_transform;
+        let absolute_linear = Mat4::from_cols(linear.x_axis.abs(), linear.y_axis.abs(), linear.z_axis.abs(), linear.w_axis.abs());
+        let center = transform * &self.center();
+        let half_extents = absolute_linear * self.half_extents();
+        Self {
+            min: center - half_extents,
+            max: center + half_extents
+        }
+    }
+
+    //where the ray enters the box (0 if it starts inside), if that's no further than max_distance
+    pub fn get_ray_distance(&self, ray: &Ray4D, max_distance: f32) -> Option<f32> {
+        let mut entry: f32 = 0.0;
+        let mut exit = max_distance;
+        //slab test, axis by axis
+        for axis in 0..4 {
+            let (origin, direction) = (ray.origin[axis], ray.direction[axis]);
+            if direction == 0.0 {
+                if origin < self.min[axis] || origin > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let to_min = (self.min[axis] - origin) / direction;
+            let to_max = (self.max[axis] - origin) / direction;
+            entry = entry.max(to_min.min(to_max));
+            exit = exit.min(to_min.max(to_max));
+        }
+        (entry <= exit).then_some(entry)
+    }
+
+    pub fn intersects_aabb(&self, other: &Self) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    pub fn intersects_hypersphere(&self, hypersphere: &BoundingHypersphere4D) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let closest_point = hypersphere.center.clamp(self.min, self.max);
+        closest_point.distance_squared(hypersphere.center) <= hypersphere.radius * hypersphere.radius
+    }
+
+    pub fn intersects_hyperplane(&self, hyperplane: &Hyperplane4D) -> bool {
+        let reach = self.half_extents().dot(hyperplane.normal.abs());
+        !self.is_empty() && hyperplane.get_signed_distance(self.center()).abs() <= reach
+    }
+}
+
+impl BoundingHypersphere4D {
+    //centered on the bounding box, so not the smallest, but close for the usual meshes
+    pub fn from_points<I: IntoIterator<Item = Vec4> + Clone>(points: I) -> Self {
+        let center = Aabb4D::from_points(points.clone()).center();
+        let radius = points.into_iter().map(|point| point.distance(center)).fold(0.0, f32::max);
+        Self { center, radius }
+    }
+
+    //for rotations and scales, like the ones from Transform4D, the result is exact for uniform scales
+    pub fn transformed(&self, transform: &AffineTransform4D) -> Self {
+        let linear = transform.linear_transform;
+        let max_scale = [linear.x_axis, linear.y_axis, linear.z_axis, linear.w_axis].iter()
+            .map(|column| column.length())
+            .fold(0.0, f32::max);
+        Self {
+            center: transform * &self.center,
+            radius: self.radius * max_scale
+        }
+    }
+
+    pub fn get_aabb(&self) -> Aabb4D {
+        Aabb4D {
+            min: self.center - Vec4::splat(self.radius),
+            max: self.center + Vec4::splat(self.radius)
+        }
+    }
+
+    pub fn contains_point(&self, point: Vec4) -> bool {
+        point.distance_squared(self.center) <= self.radius * self.radius
+    }
+
+    //where the ray enters the hypersphere (0 if it starts inside)
+    pub fn get_ray_distance(&self, ray: &Ray4D) -> Option<f32> {
+        let to_center = self.center - ray.origin;
+        let closest_approach = to_center.dot(ray.direction);
+        let squared_miss_distance = to_center.length_squared() - closest_approach * closest_approach;
+        let squared_half_chord = self.radius * self.radius - squared_miss_distance;
+        if squared_half_chord < 0.0 {
+            return None;
+        }
+        let exit = closest_approach + squared_half_chord.sqrt();
+        (exit >= 0.0).then_some((closest_approach - squared_half_chord.sqrt()).max(0.0))
+    }
+
+    pub fn intersects_hypersphere(&self, other: &Self) -> bool {
+        self.center.distance(other.center) <= self.radius + other.radius
+    }
+
+    pub fn intersects_hyperplane(&self, hyperplane: &Hyperplane4D) -> bool {
+        hyperplane.get_signed_distance(self.center).abs() <= self.radius
+    }
+}
+
+impl Hyperplane4D {
+    pub fn new(normal: Vec4, point: Vec4) -> Self {
+        let normal = normal.normalize();
+        Self {
+            normal,
+            distance: normal.dot(point)
+        }
+    }
+
+    //positive on the side the normal points to
+    pub fn get_signed_distance(&self, point: Vec4) -> f32 {
+        self.normal.dot(point) - self.distance
+    }
+
+    //the hyperplane through the transformed points, e.g. to bring it into the local space of an object with the inverse of its transform
+    pub fn transformed(&self, transform: &AffineTransform4D) -> Self {
+        let normal = transform.point_transform_to_normal_transform() * self.normal;
+        let point = transform * &(self.normal * self.distance);
+        Self::new(normal, point)
+    }
+}
+
+impl Mesh4D {
+    pub fn get_aabb(&self) -> Aabb4D {
+        Aabb4D::from_points(self.vertices.iter().map(|vertex| vertex.position))
+    }
+
+    pub fn get_bounding_hypersphere(&self) -> BoundingHypersphere4D {
+        BoundingHypersphere4D::from_points(self.vertices.iter().map(|vertex| vertex.position))
+    }
+
+    pub fn get_tetrahedron(&self, index: usize) -> [Vec4; 4] {
+        self.indeces[index].map(|vertex_index| self.vertices[vertex_index].position)
+    }
+}
+
+/* The closest point to point on a simplex with up to 4 vertices (point, edge, triangle or tetrahedron).
+   It's the projection onto the affine hull if that lies inside, else it's on one of the faces. */
+pub fn get_closest_point_on_simplex(vertices: &[Vec4], point: Vec4) -> Vec4 {
+    let origin = vertices[0];
+    if vertices.len() == 1 {
+        return origin;
+    }
+
+    //least squares for the barycentric coordinates of the edges from the first vertex, padded to 3x3
+    let edges: Vec<Vec4> = vertices[1..].iter().map(|vertex| *vertex - origin).collect();
+    let get_gram_column = |column: usize| Vec3::from_array(std::array::from_fn(|row| match (edges.get(column), edges.get(row)) {
+        (Some(a), Some(b)) => a.dot(*b),
+        _ => if column == row { 1.0 } else { 0.0 }
+    }));
+    let gram_matrix = Mat3::from_cols(get_gram_column(0), get_gram_column(1), get_gram_column(2));
+    let right_side = Vec3::from_array(std::array::from_fn(|row| edges.get(row).map_or(0.0, |edge| edge.dot(point - origin))));
+
+    if gram_matrix.determinant().abs() > f32::EPSILON * gram_matrix.x_axis.length() * gram_matrix.y_axis.length() * gram_matrix.z_axis.length() {
+        let coordinates = gram_matrix.inverse() * right_side;
+        let coordinates = &coordinates.to_array()[..edges.len()];
+        if coordinates.iter().all(|c| *c >= 0.0) && coordinates.iter().sum::<f32>() <= 1.0 {
+            return origin + edges.iter().zip(coordinates).map(|(edge, c)| *edge * *c).sum::<Vec4>();
+        }
+    }
+
+    (0..vertices.len())
+        .map(|skipped| {
+            let face: Vec<Vec4> = vertices.iter().enumerate().filter(|(i, _)| *i != skipped).map(|(_, vertex)| *vertex).collect();
+            get_closest_point_on_simplex(&face, point)
+        })
+        .min_by(|a, b| a.distance_squared(point).total_cmp(&b.distance_squared(point)))
+        .unwrap()
+}
@@ -0,0 +1,516 @@
+use super::bounds::{Aabb4D, BoundingHypersphere4D, Hyperplane4D, get_closest_point_on_simplex};
+use super::entity::{EntityId, Collider4D};
+use super::picking::{self, Ray4D, RayHit4D};
+use super::scene::Scene4D;
+use super::transform::AffineTransform4D;
+use crate::renderer::mesh::Mesh4D;
+use glam::Vec4;
+use std::ops::Range;
+
+const MAX_LEAF_SIZE: usize = 4;
+
+/* A binary tree of bounding boxes over items that are only known by their index, like the tetrahedra of a mesh
+   or the entities of a scene. The queries only prune with the boxes, the exact tests are up to the caller. */
+#[derive(Debug)]
+pub struct Bvh4D {
+    nodes: Vec<BvhNode4D>,//the root comes first
+    item_indeces: Vec<usize>//leaves refer to ranges of these
+}
+#[derive(Debug)]
+struct BvhNode4D {
+    aabb: Aabb4D,
+    content: BvhNodeContent
+}
+#[derive(Debug)]
+enum BvhNodeContent {
+    Leaf { items: Range<usize> },
+    Inner { left: usize, right: usize }
+}
+
+impl Bvh4D {
+    pub fn new(item_aabbs: &[Aabb4D]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            item_indeces: (0..item_aabbs.len()).collect()
+        };
+        if !item_aabbs.is_empty() {
+            bvh.build_node(item_aabbs, 0..item_aabbs.len());
+        }
+        bvh
+    }
+
+    //splits at the median along the longest axis of the item centers, returns the index of the node
+    fn build_node(&mut self, item_aabbs: &[Aabb4D], items: Range<usize>) -> usize {
+        let node_items = &mut self.item_indeces[items.clone()];
+        let aabb = node_items.iter().fold(Aabb4D::EMPTY, |aabb, item| aabb.union(&item_aabbs[*item]));
+        let node_index = self.nodes.len();
+        self.nodes.push(BvhNode4D {
+            aabb,
+            content: BvhNodeContent::Leaf { items: items.clone() }
+        });
+        if items.len() <= MAX_LEAF_SIZE {
+            return node_index;
+        }
+
+        let center_bounds = Aabb4D::from_points(node_items.iter().map(|item| item_aabbs[*item].center()));
+        let extents = center_bounds.max - center_bounds.min;
+        let axis = (0..4).max_by(|a, b| extents[*a].total_cmp(&extents[*b])).unwrap();
+        let middle = items.len() / 2;
+        node_items.select_nth_unstable_by(middle, |a, b| item_aabbs[*a].center()[axis].total_cmp(&item_aabbs[*b].center()[axis]));
+
+        let left = self.build_node(item_aabbs, items.start..items.start + middle);
+        let right = self.build_node(item_aabbs, items.start + middle..items.end);
+        self.nodes[node_index].content = BvhNodeContent::Inner { left, right };
+        node_index
+    }
+
+    //recomputes the boxes after the items moved, but keeps the tree. Queries stay exact, they just get slower the further the items move
+    pub fn refit(&mut self, item_aabbs: &[Aabb4D]) {
+        //children come after their parent
+        for node_index in (0..self.nodes.len()).rev() {
+            self.nodes[node_index].aabb = match &self.nodes[node_index].content {
+                BvhNodeContent::Leaf { items } => self.item_indeces[items.clone()].iter().fold(Aabb4D::EMPTY, |aabb, item| aabb.union(&item_aabbs[*item])),
+                BvhNodeContent::Inner { left, right } => self.nodes[*left].aabb.union(&self.nodes[*right].aabb)
+            };
+        }
+    }
+
+    pub fn get_aabb(&self) -> Aabb4D {
+        self.nodes.first().map_or(Aabb4D::EMPTY, |root| root.aabb)
+    }
+
+    //the closest item hit, with its distance. Boxes are visited front to back, and skipped once they are behind the closest hit so far
+    pub fn ray_cast<F: FnMut(usize) -> Option<f32>>(&self, ray: &Ray4D, mut get_item_distance: F) -> Option<(usize, f32)> {
+        let mut closest_hit: Option<(usize, f32)> = None;
+        let root_distance = self.nodes.first()?.aabb.get_ray_distance(ray, f32::INFINITY)?;
+        let mut stack = vec![(0, root_distance)];
+        while let Some((node_index, node_distance)) = stack.pop() {
+            let max_distance = closest_hit.map_or(f32::INFINITY, |(_, distance)| distance);
+            if node_distance > max_distance {
+                continue;
+            }
+            match &self.nodes[node_index].content {
+                BvhNodeContent::Leaf { items } => {
+                    for item in &self.item_indeces[items.clone()] {
+                        if let Some(distance) = get_item_distance(*item) {
+                            if closest_hit.is_none_or(|(_, closest_distance)| distance < closest_distance) {
+                                closest_hit = Some((*item, distance));
+                            }
+                        }
+                    }
+                },
+                BvhNodeContent::Inner { left, right } => {
+                    let mut children: Vec<_> = [*left, *right].into_iter()
+                        .filter_map(|child| self.nodes[child].aabb.get_ray_distance(ray, max_distance).map(|distance| (child, distance)))
+                        .collect();
+                    //the nearer child is popped first
+                    children.sort_by(|a, b| b.1.total_cmp(&a.1));
+                    stack.extend(children);
+                }
+            }
+        }
+        closest_hit
+    }
+
+    //the items whose boxes intersect the hypersphere
+    pub fn get_items_in_hypersphere(&self, hypersphere: &BoundingHypersphere4D) -> Vec<usize> {
+        self.get_items_where(|aabb| aabb.intersects_hypersphere(hypersphere))
+    }
+
+    pub fn get_items_in_aabb(&self, aabb: &Aabb4D) -> Vec<usize> {
+        self.get_items_where(|node_aabb| node_aabb.intersects_aabb(aabb))
+    }
+
+    //the items whose boxes intersect the hyperplane
+    pub fn get_items_on_hyperplane(&self, hyperplane: &Hyperplane4D) -> Vec<usize> {
+        self.get_items_where(|aabb| aabb.intersects_hyperplane(hyperplane))
+    }
+
+    fn get_items_where<F: Fn(&Aabb4D) -> bool>(&self, is_relevant: F) -> Vec<usize> {
+        let mut items = Vec::new();
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !is_relevant(&node.aabb) {
+                continue;
+            }
+            match &node.content {
+                BvhNodeContent::Leaf { items: node_items } => items.extend_from_slice(&self.item_indeces[node_items.clone()]),
+                BvhNodeContent::Inner { left, right } => stack.extend([*left, *right])
+            }
+        }
+        items
+    }
+}
+
+//a mesh for spatial queries, with a BVH over its tetrahedra. Shared by the entities using the mesh, like the uploaded mesh
+#[derive(Debug)]
+pub struct MeshCollider4D {
+    pub mesh: Mesh4D,
+    pub bounding_hypersphere: BoundingHypersphere4D,
+    bvh: Bvh4D
+}
+impl MeshCollider4D {
+    pub fn new(mesh: Mesh4D) -> Self {
+        let tetrahedron_aabbs: Vec<_> = (0..mesh.indeces.len())
+            .map(|index| Aabb4D::from_points(mesh.get_tetrahedron(index)))
+            .collect();
+        Self {
+            bounding_hypersphere: mesh.get_bounding_hypersphere(),
+            bvh: Bvh4D::new(&tetrahedron_aabbs),
+            mesh
+        }
+    }
+
+    pub fn get_aabb(&self) -> Aabb4D {
+        self.bvh.get_aabb()
+    }
+
+    //like picking::ray_cast_mesh_4D, which tests every tetrahedron
+    pub fn ray_cast(&self, ray: &Ray4D) -> Option<(f32, Vec4)> {
+        let (index, distance) = self.bvh.ray_cast(ray, |index| picking::ray_cast_tetrahedron(ray, self.mesh.get_tetrahedron(index)))?;
+        let [a, b, c, d] = self.mesh.get_tetrahedron(index);
+        Some((distance, picking::get_perpendicular_4D(b - a, c - a, d - a)))
+    }
+
+    //the indeces of the tetrahedra that intersect the hypersphere, in no particular order
+    pub fn get_tetrahedra_in_hypersphere(&self, hypersphere: &BoundingHypersphere4D) -> Vec<usize> {
+        let mut tetrahedra = self.bvh.get_items_in_hypersphere(hypersphere);
+        tetrahedra.retain(|index| is_tetrahedron_in_hypersphere(self.mesh.get_tetrahedron(*index), hypersphere));
+        tetrahedra
+    }
+
+    //the indeces of the tetrahedra that intersect the hyperplane, in no particular order
+    pub fn get_tetrahedra_on_hyperplane(&self, hyperplane: &Hyperplane4D) -> Vec<usize> {
+        let mut tetrahedra = self.bvh.get_items_on_hyperplane(hyperplane);
+        tetrahedra.retain(|index| is_tetrahedron_on_hyperplane(self.mesh.get_tetrahedron(*index), hyperplane));
+        tetrahedra
+    }
+}
+
+pub fn is_tetrahedron_in_hypersphere(tetrahedron: [Vec4; 4], hypersphere: &BoundingHypersphere4D) -> bool {
+    hypersphere.contains_point(get_closest_point_on_simplex(&tetrahedron, hypersphere.center))
+}
+
+pub fn is_tetrahedron_on_hyperplane(tetrahedron: [Vec4; 4], hyperplane: &Hyperplane4D) -> bool {
+    let distances = tetrahedron.map(|vertex| hyperplane.get_signed_distance(vertex));
+    distances.iter().any(|distance| *distance <= 0.0) && distances.iter().any(|distance| *distance >= 0.0)
+}
+
+/* Over the world space bounding boxes of the entities with a transform and a collider.
+   Entities move, so call update after changing the scene. World4D keeps one up to date */
+pub struct SceneBvh4D {
+    entities: Vec<EntityId>,
+    bvh: Bvh4D
+}
+impl SceneBvh4D {
+    pub fn new(scene: &Scene4D) -> Self {
+        let (entities, aabbs) = Self::get_entity_aabbs(scene);
+        Self {
+            bvh: Bvh4D::new(&aabbs),
+            entities
+        }
+    }
+
+    //refits if the same entities have colliders as before, else rebuilds
+    pub fn update(&mut self, scene: &Scene4D) {
+        let (entities, aabbs) = Self::get_entity_aabbs(scene);
+        if entities == self.entities {
+            self.bvh.refit(&aabbs);
+        } else {
+            *self = Self {
+                bvh: Bvh4D::new(&aabbs),
+                entities
+            };
+        }
+    }
+
+    fn get_entity_aabbs(scene: &Scene4D) -> (Vec<EntityId>, Vec<Aabb4D>) {
+        scene.colliders.iter()
+            .filter_map(|(entity, collider)| {
+                let transform = scene.transforms.get(entity)?.as_matrix();
+                Some((entity, get_collider_aabb(collider, &transform)))
+            })
+            .unzip()
+    }
+
+    //like picking::ray_cast_4D
+    pub fn ray_cast(&self, scene: &Scene4D, ray: &Ray4D) -> Option<RayHit4D> {
+        let ray_cast_entity = |entity: EntityId| picking::ray_cast_collider_4D(scene.colliders.get(entity)?, &scene.transforms.get(entity)?.as_matrix(), ray);
+        let (index, _) = self.bvh.ray_cast(ray, |index| ray_cast_entity(self.entities[index]).map(|(distance, _)| distance))?;
+        //the normal is only needed for the closest hit
+        let entity = self.entities[index];
+        let (distance, normal) = ray_cast_entity(entity)?;
+        Some(RayHit4D {
+            entity,
+            distance,
+            point: ray.at(distance),
+            normal
+        })
+    }
+
+    //exact for mesh colliders, and for hypersphere colliders with a uniform scale
+    pub fn get_entities_in_hypersphere(&self, scene: &Scene4D, hypersphere: &BoundingHypersphere4D) -> Vec<EntityId> {
+        self.get_entities_where(scene, self.bvh.get_items_in_hypersphere(hypersphere), |collider, transform| collider_intersects_hypersphere(collider, transform, hypersphere))
+    }
+
+    pub fn get_entities_on_hyperplane(&self, scene: &Scene4D, hyperplane: &Hyperplane4D) -> Vec<EntityId> {
+        self.get_entities_where(scene, self.bvh.get_items_on_hyperplane(hyperplane), |collider, transform| collider_intersects_hyperplane(collider, transform, hyperplane))
+    }
+
+    fn get_entities_where<F: Fn(&Collider4D, &AffineTransform4D) -> bool>(&self, scene: &Scene4D, candidates: Vec<usize>, intersects: F) -> Vec<EntityId> {
+        candidates.into_iter()
+            .map(|index| self.entities[index])
+            .filter(|entity| match (scene.colliders.get(*entity), scene.transforms.get(*entity)) {
+                (Some(collider), Some(transform)) => intersects(collider, &transform.as_matrix()),
+                _ => false
+            })
+            .collect()
+    }
+}
+
+//in world space
+pub fn get_collider_aabb(collider: &Collider4D, transform: &AffineTransform4D) -> Aabb4D {
+    match collider {
+        Collider4D::Hypersphere { radius } => BoundingHypersphere4D { center: Vec4::ZERO, radius: *radius }.transformed(transform).get_aabb(),
+        Collider4D::Mesh(mesh_collider) => mesh_collider.get_aabb().transformed(transform)
+    }
+}
+pub fn get_collider_bounding_hypersphere(collider: &Collider4D, transform: &AffineTransform4D) -> BoundingHypersphere4D {
+    match collider {
+        Collider4D::Hypersphere { radius } => BoundingHypersphere4D { center: Vec4::ZERO, radius: *radius }.transformed(transform),
+        Collider4D::Mesh(mesh_collider) => mesh_collider.bounding_hypersphere.transformed(transform)
+    }
+}
+
+//the hypersphere is in world space. Mesh tetrahedra are only transformed if their local box could touch it
+pub fn collider_intersects_hypersphere(collider: &Collider4D, transform: &AffineTransform4D, hypersphere: &BoundingHypersphere4D) -> bool {
+    match collider {
+        Collider4D::Hypersphere { .. } => get_collider_bounding_hypersphere(collider, transform).intersects_hypersphere(hypersphere),
+        Collider4D::Mesh(mesh_collider) => {
+            let local_aabb = hypersphere.get_aabb().transformed(&transform.inverse());
+            mesh_collider.bvh.get_items_in_aabb(&local_aabb).into_iter()
+                .any(|index| is_tetrahedron_in_hypersphere(mesh_collider.mesh.get_tetrahedron(index).map(|vertex| transform * &vertex), hypersphere))
+        }
+    }
+}
+
+//hyperplanes stay hyperplanes in local space, even with a non-uniform scale, so this is exact
+pub fn collider_intersects_hyperplane(collider: &Collider4D, transform: &AffineTransform4D, hyperplane: &Hyperplane4D) -> bool {
+    let local_hyperplane = hyperplane.transformed(&transform.inverse());
+    match collider {
+        Collider4D::Hypersphere { radius } => local_hyperplane.get_signed_distance(Vec4::ZERO).abs() <= *radius,
+        Collider4D::Mesh(mesh_collider) => !mesh_collider.get_tetrahedra_on_hyperplane(&local_hyperplane).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::transform::{Transform4D, rotation};
+    use crate::renderer::mesh::primitives;
+    use rand::{rngs::SmallRng, SeedableRng, Rng};
+    use std::collections::BTreeSet;
+
+    const QUERY_COUNT: usize = 100;
+    const DISTANCE_TOLERANCE: f32 = 1e-3;
+
+    fn test_meshes() -> Vec<Mesh4D> {
+        vec![primitives::tesseract_4D(), primitives::sphere_4D(3, 1)]
+    }
+
+    /* Tesseracts with random non-uniform scales, and hyperspheres with uniform ones. The colliders themselves are
+       covered by mesh_queries_match_brute_force, so these are about the BVH over the entities */
+    fn test_scene(rng: &mut SmallRng) -> Scene4D {
+        let tesseract = Collider4D::from_mesh(primitives::tesseract_4D());
+        let mut scene = Scene4D::new();
+        for _ in 0..40 {
+            let entity = scene.spawn();
+            let (collider, scale) = match rng.gen_bool(0.5) {
+                true => (Collider4D::Hypersphere { radius: rng.gen_range(0.2..1.0) }, Vec4::splat(rng.gen_range(0.5..2.0))),
+                false => (tesseract.clone(), Vec4::from_array(std::array::from_fn(|_| rng.gen_range(0.3..2.0))))
+            };
+            scene.colliders.insert(entity, collider);
+            scene.transforms.insert(entity, Transform4D {
+                position: Vec4::from_array(std::array::from_fn(|_| rng.gen_range(-8.0..8.0))),
+                orientation: rotation::random_4D_uniform(rng),
+                scale
+            });
+        }
+        //entities without a transform can't be hit
+        let entity = scene.spawn();
+        scene.colliders.insert(entity, Collider4D::Hypersphere { radius: 1.0 });
+        scene
+    }
+
+    fn assert_distances_equal(bvh_distance: Option<f32>, brute_force_distance: Option<f32>, ray: &Ray4D) {
+        let are_equal = match (bvh_distance, brute_force_distance) {
+            (Some(a), Some(b)) => (a - b).abs() <= DISTANCE_TOLERANCE * a.abs().max(1.0),
+            (None, None) => true,
+            _ => false
+        };
+        assert!(are_equal, "{ray:?}: BVH hit at {bvh_distance:?}, brute force at {brute_force_distance:?}");
+    }
+
+    //the queries are spread over the bounds and a bit around them, so that some of them miss
+    fn random_point(bounds: &Aabb4D, rng: &mut SmallRng) -> Vec4 {
+        let margin = bounds.half_extents() * 0.5;
+        Vec4::from_array(std::array::from_fn(|axis| rng.gen_range(bounds.min[axis] - margin[axis]..=bounds.max[axis] + margin[axis])))
+    }
+
+    fn random_direction(rng: &mut SmallRng) -> Vec4 {
+        loop {
+            let direction = Vec4::from_array(std::array::from_fn(|_| rng.gen_range(-1.0..1.0)));
+            if (0.01..=1.0).contains(&direction.length_squared()) {
+                return direction.normalize();
+            }
+        }
+    }
+
+    //half of them are aimed into the bounds, since rays in random directions mostly miss
+    fn random_ray(bounds: &Aabb4D, rng: &mut SmallRng) -> Ray4D {
+        let origin = random_point(bounds, rng);
+        let direction = match rng.gen_bool(0.5) {
+            true => (random_point(bounds, rng) - origin).try_normalize().unwrap_or(Vec4::W),
+            false => random_direction(rng)
+        };
+        Ray4D::new(origin, direction)
+    }
+
+    fn random_hypersphere(bounds: &Aabb4D, rng: &mut SmallRng) -> BoundingHypersphere4D {
+        BoundingHypersphere4D {
+            center: random_point(bounds, rng),
+            radius: rng.gen_range(0.0..=0.5) * bounds.half_extents().max_element()
+        }
+    }
+
+    fn random_hyperplane(bounds: &Aabb4D, rng: &mut SmallRng) -> Hyperplane4D {
+        Hyperplane4D::new(random_direction(rng), random_point(bounds, rng))
+    }
+
+    fn random_aabb(bounds: &Aabb4D, rng: &mut SmallRng) -> Aabb4D {
+        Aabb4D::from_points([random_point(bounds, rng), random_point(bounds, rng)])
+    }
+
+    fn get_scene_bounds(scene: &Scene4D) -> Aabb4D {
+        scene.colliders.iter()
+            .filter_map(|(entity, collider)| Some(get_collider_aabb(collider, &scene.transforms.get(entity)?.as_matrix())))
+            .fold(Aabb4D::EMPTY, |bounds, aabb| bounds.union(&aabb))
+    }
+
+    fn assert_scene_queries_match_brute_force(scene: &Scene4D, scene_bvh: &SceneBvh4D, rng: &mut SmallRng) {
+        let bounds = get_scene_bounds(scene);
+        let colliders: Vec<_> = scene.colliders.iter()
+            .filter_map(|(entity, collider)| Some((entity, collider, scene.transforms.get(entity)?.as_matrix())))
+            .collect();
+        for _ in 0..QUERY_COUNT {
+            let ray = random_ray(&bounds, rng);
+            let brute_force_distance = colliders.iter()
+                .filter_map(|(_, collider, transform)| picking::ray_cast_collider_4D(collider, transform, &ray).map(|(distance, _)| distance))
+                .min_by(|a, b| a.total_cmp(b));
+            assert_distances_equal(scene_bvh.ray_cast(scene, &ray).map(|hit| hit.distance), brute_force_distance, &ray);
+
+            let hypersphere = random_hypersphere(&bounds, rng);
+            let bvh_entities: BTreeSet<_> = scene_bvh.get_entities_in_hypersphere(scene, &hypersphere).into_iter().collect();
+            let brute_force_entities: BTreeSet<_> = colliders.iter()
+                .filter(|(_, collider, transform)| collider_intersects_hypersphere(collider, transform, &hypersphere))
+                .map(|(entity, _, _)| *entity)
+                .collect();
+            assert_eq!(bvh_entities, brute_force_entities, "{hypersphere:?}");
+
+            let hyperplane = random_hyperplane(&bounds, rng);
+            let bvh_entities: BTreeSet<_> = scene_bvh.get_entities_on_hyperplane(scene, &hyperplane).into_iter().collect();
+            let brute_force_entities: BTreeSet<_> = colliders.iter()
+                .filter(|(_, collider, transform)| collider_intersects_hyperplane(collider, transform, &hyperplane))
+                .map(|(entity, _, _)| *entity)
+                .collect();
+            assert_eq!(bvh_entities, brute_force_entities, "{hyperplane:?}");
+        }
+    }
+
+    #[test]
+    fn mesh_queries_match_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(5);
+        for mesh in test_meshes() {
+            let tetrahedron_count = mesh.indeces.len();
+            let mesh_collider = MeshCollider4D::new(mesh);
+            let mesh = &mesh_collider.mesh;
+            let bounds = mesh_collider.get_aabb();
+            for _ in 0..QUERY_COUNT {
+                let ray = random_ray(&bounds, &mut rng);
+                let bvh_distance = mesh_collider.ray_cast(&ray).map(|(distance, _)| distance);
+                let brute_force_distance = picking::ray_cast_mesh_4D(mesh, &ray).map(|(distance, _)| distance);
+                assert_distances_equal(bvh_distance, brute_force_distance, &ray);
+
+                let hypersphere = random_hypersphere(&bounds, &mut rng);
+                let bvh_tetrahedra: BTreeSet<_> = mesh_collider.get_tetrahedra_in_hypersphere(&hypersphere).into_iter().collect();
+                let brute_force_tetrahedra: BTreeSet<_> = (0..tetrahedron_count)
+                    .filter(|index| is_tetrahedron_in_hypersphere(mesh.get_tetrahedron(*index), &hypersphere))
+                    .collect();
+                assert_eq!(bvh_tetrahedra, brute_force_tetrahedra, "{tetrahedron_count} tetrahedra, {hypersphere:?}");
+
+                let hyperplane = random_hyperplane(&bounds, &mut rng);
+                let bvh_tetrahedra: BTreeSet<_> = mesh_collider.get_tetrahedra_on_hyperplane(&hyperplane).into_iter().collect();
+                let brute_force_tetrahedra: BTreeSet<_> = (0..tetrahedron_count)
+                    .filter(|index| is_tetrahedron_on_hyperplane(mesh.get_tetrahedron(*index), &hyperplane))
+                    .collect();
+                assert_eq!(bvh_tetrahedra, brute_force_tetrahedra, "{tetrahedron_count} tetrahedra, {hyperplane:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn aabb_queries_match_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(6);
+        let mesh = primitives::sphere_4D(3, 1);
+        let item_aabbs: Vec<_> = (0..mesh.indeces.len())
+            .map(|index| Aabb4D::from_points(mesh.get_tetrahedron(index)))
+            .collect();
+        let bvh = Bvh4D::new(&item_aabbs);
+        let bounds = bvh.get_aabb();
+        for _ in 0..QUERY_COUNT {
+            let aabb = random_aabb(&bounds, &mut rng);
+            //the BVH returns whole leaves, the exact test is up to the caller
+            let candidates = bvh.get_items_in_aabb(&aabb);
+            let bvh_items: BTreeSet<_> = candidates.iter().copied()
+                .filter(|index| item_aabbs[*index].intersects_aabb(&aabb))
+                .collect();
+            assert_eq!(BTreeSet::from_iter(candidates.iter()).len(), candidates.len(), "{aabb:?}: duplicate items");
+            let brute_force_items: BTreeSet<_> = (0..item_aabbs.len())
+                .filter(|index| item_aabbs[*index].intersects_aabb(&aabb))
+                .collect();
+            assert_eq!(bvh_items, brute_force_items, "{aabb:?}");
+        }
+    }
+
+    #[test]
+    fn scene_queries_match_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let scene = test_scene(&mut rng);
+        let scene_bvh = SceneBvh4D::new(&scene);
+        assert_scene_queries_match_brute_force(&scene, &scene_bvh, &mut rng);
+    }
+
+    #[test]
+    fn scene_queries_match_brute_force_after_update() {
+        let mut rng = SmallRng::seed_from_u64(8);
+        let mut scene = test_scene(&mut rng);
+        let mut scene_bvh = SceneBvh4D::new(&scene);
+
+        //refitted
+        for (_, transform) in scene.transforms.iter_mut() {
+            transform.position += Vec4::from_array(std::array::from_fn(|_| rng.gen_range(-4.0..4.0)));
+            transform.orientation = rotation::random_4D_uniform(&mut rng);
+        }
+        scene_bvh.update(&scene);
+        assert_scene_queries_match_brute_force(&scene, &scene_bvh, &mut rng);
+
+        //rebuilt
+        scene.despawn(EntityId(3));
+        let entity = scene.spawn();
+        scene.colliders.insert(entity, Collider4D::from_mesh(primitives::tesseract_4D()));
+        scene.transforms.insert(entity, Transform4D::default());
+        scene_bvh.update(&scene);
+        assert_scene_queries_match_brute_force(&scene, &scene_bvh, &mut rng);
+    }
+}
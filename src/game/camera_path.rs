@@ -0,0 +1,160 @@
+use super::animation::{Animation4D, Keyframe4D, Interpolation, PlaybackMode};
+use super::player::{Player4D, PlayerBookmark4D};
+use super::transform::Transform4D;
+use super::transform::rotor::Rotor4D;
+use super::world::Multiverse;
+use crate::events::input::InputHandler;
+use crate::global_data::GlobalData;
+use crate::events::actions::Action;
+use serde::{Serialize, Deserialize};
+use std::path::Path;
+use std::fs;
+
+pub const DEFAULT_CAMERA_PATH_FILE: &str = "Resources/camera_path.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CameraPathFile {
+    interpolation: Interpolation,
+    playback_mode: PlaybackMode,
+    keyframes: Vec<CameraKeyframe>//sorted by time
+}
+
+//the camera, not the player, is placed at the transform
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum CameraKeyframe {
+    Transform { time: f32, transform: Transform4D },
+    Player { time: f32, player: PlayerBookmark4D }//as in bookmarks.json, so views can be copied from there
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CameraPathAction {
+    TogglePlayback,
+    TogglePause
+}
+
+//the player is put back where it was when the playback stops
+pub struct CameraPathPlayback {
+    animation: Animation4D,
+    pub is_paused: bool,
+    saved_player: PlayerBookmark4D,
+    saved_relative_camera_transform: Transform4D
+}
+impl CameraPathPlayback {
+    pub fn load(path: &Path, player: &Player4D) -> Result<Self, String> {
+        Ok(Self::new(&load_camera_path_file(path)?, player))
+    }
+
+    pub fn new(file: &CameraPathFile, player: &Player4D) -> Self {
+        let keyframes = file.keyframes.iter()
+            .map(|keyframe| {
+                let transform = match keyframe {
+                    CameraKeyframe::Transform { transform, .. } => *transform,
+                    CameraKeyframe::Player { player: bookmark, .. } => player.get_bookmark_camera_transform(bookmark)
+                };
+                Keyframe4D {
+                    time: keyframe.time(),
+                    position: transform.position,
+                    orientation: Rotor4D::from_mat4(transform.orientation),
+                    scale: transform.scale
+                }
+            })
+            .collect();
+
+        Self {
+            animation: Animation4D::keyframed(keyframes, file.interpolation, file.playback_mode),
+            is_paused: false,
+            saved_player: player.get_bookmark(),
+            saved_relative_camera_transform: player.relative_camera_transform
+        }
+    }
+
+    pub fn advance(&mut self, delta_time: f32) {
+        if !self.is_paused {
+            self.animation.advance(delta_time);
+        }
+    }
+
+    //also while paused, so single frames can be found
+    pub fn scrub(&mut self, delta_time: f32) {
+        self.animation.time = (self.animation.time + delta_time).max(0.0);
+    }
+
+    pub fn apply(&self, player: &mut Player4D) {
+        player.place_camera(self.animation.get_transform());
+    }
+
+    pub fn restore_player(&self, player: &mut Player4D) {
+        player.go_to_bookmark(&self.saved_player);
+        player.relative_camera_transform = self.saved_relative_camera_transform;
+    }
+
+    //relative to the first keyframe, within the path even when looping
+    pub fn time(&self) -> f32 {
+        self.animation.get_playback_time()
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.animation.duration()
+    }
+}
+
+//checks the keyframes, so that a loaded file can always be played
+pub fn load_camera_path_file(path: &Path) -> Result<CameraPathFile, String> {
+    let json = fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let file: CameraPathFile = serde_json::from_str(&json).map_err(|error| format!("Failed to parse {}: {error}", path.display()))?;
+    if file.keyframes.is_empty() {
+        return Err(format!("{} has no keyframes", path.display()));
+    }
+    if file.keyframes.windows(2).any(|pair| pair[0].time() > pair[1].time()) {
+        return Err(format!("The keyframes in {} aren't sorted by time", path.display()));
+    }
+    Ok(file)
+}
+
+impl CameraKeyframe {
+    fn time(&self) -> f32 {
+        match self {
+            Self::Transform { time, .. } | Self::Player { time, .. } => *time
+        }
+    }
+}
+
+pub fn do_camera_path_action(action: CameraPathAction, multiverse: &mut Multiverse, global_data: &GlobalData) {
+    let player = &mut multiverse.world_4D.player;
+    match (action, &mut multiverse.camera_path) {
+        (CameraPathAction::TogglePlayback, None) => {
+            let file = match &global_data.replayed_files {
+                Some(files) => files.camera_path.clone().ok_or("The input recording has no camera path".to_string()),
+                None => load_camera_path_file(&global_data.camera_path_file)
+            };
+            match file {
+                Ok(file) => {
+                    println!("Playing camera path {}", global_data.camera_path_file.display());
+                    let playback = CameraPathPlayback::new(&file, player);
+                    playback.apply(player);
+                    multiverse.camera_path = Some(playback);
+                },
+                Err(message) => eprintln!("{message}")
+            }
+        },
+        (CameraPathAction::TogglePlayback, Some(playback)) => {
+            playback.restore_player(player);
+            multiverse.camera_path = None;
+        },
+        (CameraPathAction::TogglePause, Some(playback)) => playback.is_paused = !playback.is_paused,
+        (CameraPathAction::TogglePause, None) => ()
+    }
+}
+
+//replaces Player4D::update while a path is playing
+pub fn update_camera_path(playback: &mut CameraPathPlayback, player: &mut Player4D, delta_time: f32, input: &InputHandler, global_data: &GlobalData) {
+    let bindings = &global_data.options.user.key_bindings;
+    let mut direction = 0.0;
+    if bindings.is_pressed(Action::ScrubCameraPathForward, input)  { direction += 1.0 };
+    if bindings.is_pressed(Action::ScrubCameraPathBackward, input) { direction -= 1.0 };
+
+    playback.advance(delta_time);
+    playback.scrub(direction * global_data.options.user.input.camera_path_scrub_speed * delta_time);
+    playback.apply(player);
+}
@@ -0,0 +1,97 @@
+use crate::renderer::mesh::{Mesh3D, Mesh4D};
+use super::scene::{Scene3D, Scene4D};
+use super::bvh::MeshCollider4D;
+use std::rc::Rc;
+
+//index into every ComponentStorage of a scene
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(pub usize);
+
+//sparse storage of one component type, indexed by EntityId
+pub struct ComponentStorage<T> {
+    components: Vec<Option<T>>
+}
+impl<T> ComponentStorage<T> {
+    pub fn new() -> Self {
+        Self {
+            components: Vec::new()
+        }
+    }
+
+    pub fn insert(&mut self, entity: EntityId, component: T) {
+        if entity.0 >= self.components.len() {
+            self.components.resize_with(entity.0 + 1, || None);
+        }
+        self.components[entity.0] = Some(component);
+    }
+
+    pub fn remove(&mut self, entity: EntityId) -> Option<T> {
+        self.components.get_mut(entity.0).and_then(|slot| slot.take())
+    }
+
+    pub fn get(&self, entity: EntityId) -> Option<&T> {
+        self.components.get(entity.0).and_then(|slot| slot.as_ref())
+    }
+
+    pub fn get_mut(&mut self, entity: EntityId) -> Option<&mut T> {
+        self.components.get_mut(entity.0).and_then(|slot| slot.as_mut())
+    }
+
+    pub fn contains(&self, entity: EntityId) -> bool {
+        self.get(entity).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &T)> {
+        self.components.iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|component| (EntityId(i), component)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut T)> {
+        self.components.iter_mut()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_mut().map(|component| (EntityId(i), component)))
+    }
+}
+impl<T> IntoIterator for ComponentStorage<T> {
+    type Item = (EntityId, T);
+    type IntoIter = std::iter::FilterMap<std::iter::Enumerate<std::vec::IntoIter<Option<T>>>, fn((usize, Option<T>)) -> Option<(EntityId, T)>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.components.into_iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.map(|component| (EntityId(i), component)))
+    }
+}
+impl<T> Default for ComponentStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//shape used for spatial queries, in the local space of the entity
+#[derive(Debug, Clone)]
+pub enum Collider3D {
+    Sphere { radius: f32 },
+    Mesh(Rc<Mesh3D>)
+}
+#[derive(Debug, Clone)]
+pub enum Collider4D {
+    Hypersphere { radius: f32 },
+    Mesh(Rc<MeshCollider4D>)
+}
+impl Collider4D {
+    pub fn from_mesh(mesh: Mesh4D) -> Self {
+        Self::Mesh(Rc::new(MeshCollider4D::new(mesh)))
+    }
+}
+
+/* Custom per-entity logic, run once per frame by the scene's update system.
+   The behavior is temporarily taken out of the scene while it runs, so it can freely modify
+   any component of any entity (including its own transform). */
+pub trait Behavior3D {
+    fn update(&mut self, entity: EntityId, scene: &mut Scene3D, delta_time: f32);
+}
+pub trait Behavior4D {
+    fn update(&mut self, entity: EntityId, scene: &mut Scene4D, delta_time: f32);
+}
@@ -0,0 +1,115 @@
+use super::world::{Multiverse, World4D};
+use super::entity::EntityId;
+use super::player::{Player4D, CameraScheme4D};
+use super::picking;
+use crate::events::input::InputHandler;
+use crate::global_data::GlobalData;
+use glam::Vec4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InspectionAction {
+    Toggle,
+    NextObject
+}
+
+//locks the 4D camera onto an object, so it can be orbited like with CameraScheme4D::Orbit. The player is put back when the inspection ends
+pub struct Inspection4D {
+    pub entity: EntityId,
+    pub distance: f32,
+    saved_player: Player4D
+}
+impl Inspection4D {
+    fn start(entity: EntityId, world: &mut World4D, global_data: &GlobalData) -> Self {
+        let target = get_target(entity, world).unwrap();
+        let player = &mut world.player;
+        let saved_player = player.clone();
+        player.switch_camera_scheme(CameraScheme4D::Orbit, global_data);
+        player.look_at(target);
+        Self {
+            entity,
+            distance: player.get_camera_world_position().distance(target),
+            saved_player
+        }
+    }
+
+    fn stop(self, player: &mut Player4D) {
+        *player = self.saved_player;
+    }
+}
+
+pub fn do_inspection_action(action: InspectionAction, multiverse: &mut Multiverse, global_data: &GlobalData) {
+    if multiverse.camera_path.is_some() {
+        println!("Can't inspect while a camera path is playing");
+        return;
+    }
+
+    let world = &mut multiverse.world_4D;
+    match (action, multiverse.inspection.take()) {
+        (InspectionAction::Toggle, None) => match get_object_in_view(world, global_data) {
+            Some(entity) => multiverse.inspection = Some(Inspection4D::start(entity, world, global_data)),
+            None => println!("Nothing to inspect in front of the camera")
+        },
+        (InspectionAction::Toggle, Some(inspection)) => inspection.stop(&mut world.player),
+        (InspectionAction::NextObject, Some(inspection)) => {
+            let entity = get_next_object(inspection.entity, world);
+            inspection.stop(&mut world.player);
+            multiverse.inspection = Some(Inspection4D::start(entity, world, global_data));
+        },
+        (InspectionAction::NextObject, None) => ()
+    }
+}
+
+//replaces Player4D::update while inspecting. Scrolling zooms. Returns false if the object is gone
+pub fn update_inspection(inspection: &mut Inspection4D, world: &mut World4D, delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) -> bool {
+    let Some(target) = get_target(inspection.entity, world) else {
+        return false;
+    };
+    inspection.distance *= (1.0 + global_data.options.user.input.inspect_zoom_step).powf(-input.scroll_delta());
+
+    world.player.do_free_rotation(delta_time, input, global_data);
+    world.player.place_camera_on_orbit(target, inspection.distance);
+    true
+}
+
+//None if the entity was despawned
+fn get_target(entity: EntityId, world: &World4D) -> Option<Vec4> {
+    world.scene.transforms.get(entity).map(|transform| transform.position)
+}
+
+//the visible objects, apart from the floor
+fn is_inspectable(entity: EntityId, world: &World4D) -> bool {
+    entity != world.floor && world.scene.meshes.contains(entity) && world.scene.transforms.contains(entity)
+}
+
+//the one under the crosshair, else the one closest to the middle of the view, ignoring everything behind the camera
+fn get_object_in_view(world: &World4D, global_data: &GlobalData) -> Option<EntityId> {
+    let hit = picking::ray_cast_4D(world, &picking::get_camera_ray_4D(&world.player, global_data));
+    if let Some(entity) = hit.map(|hit| hit.entity).filter(|entity| is_inspectable(*entity, world)) {
+        return Some(entity);
+    }
+
+    let camera_position = world.player.get_camera_world_position();
+    let forward = world.player.get_camera_world_orientation() * Vec4::W;
+    world.scene.meshes.iter()
+        .filter(|(entity, _)| is_inspectable(*entity, world))
+        .filter_map(|(entity, _)| {
+            let direction = (get_target(entity, world)? - camera_position).normalize_or_zero();
+            Some((entity, direction.dot(forward)))
+        })
+        .filter(|(_, cos)| *cos > 0.0)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+//in the order of the entity ids, wrapping around
+fn get_next_object(entity: EntityId, world: &World4D) -> EntityId {
+    let objects: Vec<_> = world.scene.meshes.iter()
+        .map(|(entity, _)| entity)
+        .filter(|entity| is_inspectable(*entity, world))
+        .collect();
+    objects.iter()
+        .find(|other| **other > entity)
+        .or(objects.first())
+        .copied()
+        .unwrap_or(entity)
+}
@@ -0,0 +1,29 @@
+use glam::{Vec3, Vec4};
+
+//point lights are placed at the position of their entity's transform
+#[derive(Debug, Copy, Clone)]
+pub enum Light3D {
+    Point {
+        color: Vec3,
+        linear_attenuation: f32,
+        quadratic_attenuation: f32
+    },
+    Directional {
+        direction: Vec3,//the direction the light travels in, in world space
+        color: Vec3,
+        casts_shadows: bool//only the first shadow casting directional light actually casts shadows
+    }
+}
+#[derive(Debug, Copy, Clone)]
+pub enum Light4D {
+    Point {
+        color: Vec3,
+        linear_attenuation: f32,
+        quadratic_attenuation: f32
+    },
+    Directional {
+        direction: Vec4,//the direction the light travels in, in world space
+        color: Vec3,
+        casts_shadows: bool//only the first shadow casting directional light actually casts shadows
+    }
+}
@@ -0,0 +1,198 @@
+use super::entity::{EntityId, Collider3D, Collider4D};
+use super::scene::Scene3D;
+use super::world::World4D;
+use super::player::{Player3D, Player4D};
+use super::transform::{AffineTransform3D, AffineTransform4D};
+use crate::renderer::mesh::{Mesh3D, Mesh4D};
+use crate::global_data::{GlobalData, VisualMode};
+use glam::{Vec3, Mat3, Vec4, Mat4, swizzles::*};
+
+//the direction is normalized, so the distance to a hit is its ray parameter
+#[derive(Debug, Copy, Clone)]
+pub struct Ray3D {
+    pub origin: Vec3,
+    pub direction: Vec3
+}
+#[derive(Debug, Copy, Clone)]
+pub struct Ray4D {
+    pub origin: Vec4,
+    pub direction: Vec4
+}
+
+//the normal faces against the ray
+#[derive(Debug, Copy, Clone)]
+pub struct RayHit3D {
+    pub entity: EntityId,
+    pub distance: f32,
+    pub point: Vec3,
+    pub normal: Vec3
+}
+#[derive(Debug, Copy, Clone)]
+pub struct RayHit4D {
+    pub entity: EntityId,
+    pub distance: f32,
+    pub point: Vec4,
+    pub normal: Vec4
+}
+
+impl Ray3D {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize()
+        }
+    }
+
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+}
+impl Ray4D {
+    pub fn new(origin: Vec4, direction: Vec4) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize()
+        }
+    }
+
+    pub fn at(&self, distance: f32) -> Vec4 {
+        self.origin + self.direction * distance
+    }
+}
+
+//through the middle of the screen, on the slice that is shown
+pub fn get_camera_ray_3D(player: &Player3D, global_data: &GlobalData) -> Ray3D {
+    let camera = player.get_camera_trs_matrix();
+    let slice_offset = match global_data.visual_mode {
+        VisualMode::Degenerate3D => global_data.slice_offset,
+        _ => 0.0
+    };
+    Ray3D::new(&camera * &(Vec3::X * slice_offset), camera.linear_transform * Vec3::Z)
+}
+pub fn get_camera_ray_4D(player: &Player4D, global_data: &GlobalData) -> Ray4D {
+    let camera = player.get_camera_trs_matrix();
+    let slice_offset = match global_data.visual_mode {
+        VisualMode::Projection4D => 0.0,
+        _ => global_data.slice_offset
+    };
+    Ray4D::new(&camera * &(Vec4::X * slice_offset), camera.linear_transform * Vec4::W)
+}
+
+//the closest hit among the entities with a transform and a collider
+pub fn ray_cast_3D(scene: &Scene3D, ray: &Ray3D) -> Option<RayHit3D> {
+    scene.colliders.iter()
+        .filter_map(|(entity, collider)| {
+            let transform = scene.transforms.get(entity)?.as_matrix();
+            let (distance, normal) = ray_cast_collider_3D(collider, &transform, ray)?;
+            Some(RayHit3D {
+                entity,
+                distance,
+                point: ray.at(distance),
+                normal
+            })
+        })
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
+//the 4D scenes have much bigger meshes, so they go through bounding volume hierarchies
+pub fn ray_cast_4D(world: &World4D, ray: &Ray4D) -> Option<RayHit4D> {
+    world.scene_bvh.ray_cast(&world.scene, ray)
+}
+
+/* The ray is moved into the local space of the collider instead of transforming every vertex. Its direction isn't
+   normalized there, so that the ray parameter still is the world space distance. Returns the distance and the world space normal. */
+pub fn ray_cast_collider_3D(collider: &Collider3D, transform: &AffineTransform3D, ray: &Ray3D) -> Option<(f32, Vec3)> {
+    let to_local = transform.inverse();
+    let local_ray = Ray3D {
+        origin: &to_local * &ray.origin,
+        direction: to_local.linear_transform * ray.direction
+    };
+    let (distance, local_normal) = match collider {
+        Collider3D::Sphere { radius } => ray_cast_sphere_3D(&local_ray, *radius)?,
+        Collider3D::Mesh(mesh) => ray_cast_mesh_3D(mesh, &local_ray)?
+    };
+
+    let normal = (transform.point_transform_to_normal_transform() * local_normal).normalize();
+    Some((distance, if normal.dot(ray.direction) > 0.0 { -normal } else { normal }))
+}
+pub fn ray_cast_collider_4D(collider: &Collider4D, transform: &AffineTransform4D, ray: &Ray4D) -> Option<(f32, Vec4)> {
+    let to_local = transform.inverse();
+    let local_ray = Ray4D {
+        origin: &to_local * &ray.origin,
+        direction: to_local.linear_transform * ray.direction
+    };
+    let (distance, local_normal) = match collider {
+        Collider4D::Hypersphere { radius } => ray_cast_hypersphere_4D(&local_ray, *radius)?,
+        Collider4D::Mesh(mesh_collider) => mesh_collider.ray_cast(&local_ray)?
+    };
+
+    let normal = (transform.point_transform_to_normal_transform() * local_normal).normalize();
+    Some((distance, if normal.dot(ray.direction) > 0.0 { -normal } else { normal }))
+}
+
+//closest triangle, with its unnormalized geometric normal
+pub fn ray_cast_mesh_3D(mesh: &Mesh3D, ray: &Ray3D) -> Option<(f32, Vec3)> {
+    mesh.indeces.iter()
+        .filter_map(|triangle| {
+            let [a, b, c] = triangle.map(|index| mesh.vertices[index].position);
+            ray_cast_triangle(ray, [a, b, c]).map(|distance| (distance, (b - a).cross(c - a)))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+//closest tetrahedron, with its unnormalized geometric normal. Tests every tetrahedron, see MeshCollider4D::ray_cast for a faster way
+pub fn ray_cast_mesh_4D(mesh: &Mesh4D, ray: &Ray4D) -> Option<(f32, Vec4)> {
+    (0..mesh.indeces.len())
+        .filter_map(|index| {
+            let [a, b, c, d] = mesh.get_tetrahedron(index);
+            ray_cast_tetrahedron(ray, [a, b, c, d]).map(|distance| (distance, get_perpendicular_4D(b - a, c - a, d - a)))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+//solves origin + t * direction = a + u * (b - a) + v * (c - a) for the barycentric coordinates u, v and the distance t
+pub fn ray_cast_triangle(ray: &Ray3D, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let system = Mat3::from_cols(b - a, c - a, -ray.direction);
+    if system.determinant().abs() < f32::EPSILON * system.x_axis.length() * system.y_axis.length() * system.z_axis.length() {
+        return None;//parallel, or a degenerate triangle
+    }
+    let [u, v, t] = (system.inverse() * (ray.origin - a)).to_array();
+    (u >= 0.0 && v >= 0.0 && u + v <= 1.0 && t >= 0.0).then_some(t)
+}
+//like ray_cast_triangle, with the third barycentric coordinate w
+pub fn ray_cast_tetrahedron(ray: &Ray4D, [a, b, c, d]: [Vec4; 4]) -> Option<f32> {
+    let system = Mat4::from_cols(b - a, c - a, d - a, -ray.direction);
+    if system.determinant().abs() < f32::EPSILON * system.x_axis.length() * system.y_axis.length() * system.z_axis.length() * system.w_axis.length() {
+        return None;
+    }
+    let [u, v, w, t] = (system.inverse() * (ray.origin - a)).to_array();
+    (u >= 0.0 && v >= 0.0 && w >= 0.0 && u + v + w <= 1.0 && t >= 0.0).then_some(t)
+}
+
+//the nearest intersection in front of the origin, or the far one from inside
+fn ray_cast_sphere_3D(ray: &Ray3D, radius: f32) -> Option<(f32, Vec3)> {
+    let t = solve_ray_sphere(ray.direction.length_squared(), ray.origin.dot(ray.direction), ray.origin.length_squared() - radius * radius)?;
+    Some((t, ray.at(t)))
+}
+fn ray_cast_hypersphere_4D(ray: &Ray4D, radius: f32) -> Option<(f32, Vec4)> {
+    let t = solve_ray_sphere(ray.direction.length_squared(), ray.origin.dot(ray.direction), ray.origin.length_squared() - radius * radius)?;
+    Some((t, ray.at(t)))
+}
+
+//smallest non-negative root of a * t^2 + 2 * half_b * t + c
+fn solve_ray_sphere(a: f32, half_b: f32, c: f32) -> Option<f32> {
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    [(-half_b - root) / a, (-half_b + root) / a].into_iter().find(|t| *t >= 0.0)
+}
+
+//the 4D analogue of the cross product, perpendicular to all three vectors. Each component is a 3x3 minor, like in the 3D cross product
+pub fn get_perpendicular_4D(a: Vec4, b: Vec4, c: Vec4) -> Vec4 {
+    Vec4::new(
+        -Mat3::from_cols(a.yzw(), b.yzw(), c.yzw()).determinant(),
+         Mat3::from_cols(a.xzw(), b.xzw(), c.xzw()).determinant(),
+        -Mat3::from_cols(a.xyw(), b.xyw(), c.xyw()).determinant(),
+         Mat3::from_cols(a.xyz(), b.xyz(), c.xyz()).determinant()
+    )
+}
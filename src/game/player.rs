@@ -1,11 +1,14 @@
 use super::transform::{Transform3D, AffineTransform3D, matrix3x3, Transform4D, AffineTransform4D, matrix4x4, rotation};
-use crate::events::input::InputHandler;
+use super::transform::rotor::Rotor4D;
+use crate::events::input::{InputHandler, GamepadAxes};
 use crate::global_data::GlobalData;
 use glam::{Vec2, Vec3, Vec4, Mat3, Mat4, Quat};
 use std::f32::consts::TAU;
 use std::f32;
-use glium::glutin::event::{VirtualKeyCode, MouseButton};
+use crate::events::actions::Action;
+use serde::{Serialize, Deserialize};
 
+#[derive(Serialize, Deserialize)]
 pub struct Player3D {
     pub transform: Transform3D,
     pub relative_camera_transform: Transform3D,
@@ -47,20 +50,36 @@ impl Player3D {
         )
     }
 
+    pub fn get_bookmark(&self) -> PlayerBookmark3D {
+        PlayerBookmark3D {
+            transform: self.transform,
+            look_direction: self.look_direction
+        }
+    }
+
+    pub fn go_to_bookmark(&mut self, bookmark: &PlayerBookmark3D) {
+        self.transform = bookmark.transform;
+        self.look_direction = bookmark.look_direction;
+        self.relative_camera_transform.orientation = rotation::around_x(self.look_direction.y);
+    }
+
     fn do_linear_movement(&mut self, delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
+        let bindings = &global_data.options.user.key_bindings;
         let mut pos_delta = Vec3::ZERO;
-        if input.keyboard_is_pressed(&VirtualKeyCode::A     ) { pos_delta += Vec3::NEG_X };
-        if input.keyboard_is_pressed(&VirtualKeyCode::D     ) { pos_delta += Vec3::X     };
-        if input.keyboard_is_pressed(&VirtualKeyCode::LShift) { pos_delta += Vec3::NEG_Y };
-        if input.keyboard_is_pressed(&VirtualKeyCode::Space ) { pos_delta += Vec3::Y     };
-        if input.keyboard_is_pressed(&VirtualKeyCode::S     ) { pos_delta += Vec3::NEG_Z };
-        if input.keyboard_is_pressed(&VirtualKeyCode::W     ) { pos_delta += Vec3::Z     };
+        if bindings.is_pressed(Action::MoveLeft, input)     { pos_delta += Vec3::NEG_X };
+        if bindings.is_pressed(Action::MoveRight, input)    { pos_delta += Vec3::X     };
+        if bindings.is_pressed(Action::MoveDown, input)     { pos_delta += Vec3::NEG_Y };
+        if bindings.is_pressed(Action::MoveUp, input)       { pos_delta += Vec3::Y     };
+        if bindings.is_pressed(Action::MoveBackward, input) { pos_delta += Vec3::NEG_Z };
+        if bindings.is_pressed(Action::MoveForward, input)  { pos_delta += Vec3::Z     };
+        let gamepad = get_gamepad_axes(input, global_data);
+        pos_delta += Vec3::new(gamepad.left_stick.x, 0.0, gamepad.left_stick.y) * global_data.options.user.input.gamepad_move_sensitivity;
         pos_delta = self.transform.orientation * pos_delta;
         self.transform.position += pos_delta * delta_time * global_data.options.dev.player.walking_speed;
     }
         
-    fn do_rotation(&mut self, _delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
-        self.look_direction -= input.mouse_delta() * global_data.options.user.input.mouse_sensitivity;
+    fn do_rotation(&mut self, delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
+        self.look_direction -= get_look_delta(delta_time, input, global_data);
         self.look_direction.x = self.look_direction.x.rem_euclid(TAU);//keep within reasonable range to prevent precision issues
         self.look_direction.y = self.look_direction.y.clamp(-TAU / 4.0, TAU / 4.0);
 
@@ -69,11 +88,48 @@ impl Player3D {
     }
 }
 
+//the state that isn't recomputed every frame, enough to restore the camera exactly
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PlayerBookmark3D {
+    pub transform: Transform3D,
+    pub look_direction: Vec2
+}
+
+/* How the mouse rotates the 4D camera. The planes are named after the axes that move, with x being the one perpendicular to the slice
+    Classic: keeps z up. Turns in xy and looks ana/kata in xw. With alternative_look held, turns in yw and tilts in zw
+    FreePlanes: rotates freely without an up direction. Turns in yw and pitches in zw, with alternative_look in xy and xw, and with roll_look in yz and xz
+    Orbit: like FreePlanes, but around a point in front of the camera, which the movement keys move */
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CameraScheme4D {
+    #[default]
+    Classic,
+    FreePlanes,
+    Orbit
+}
+impl CameraScheme4D {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Classic => Self::FreePlanes,
+            Self::FreePlanes => Self::Orbit,
+            Self::Orbit => Self::Classic
+        }
+    }
+
+    fn is_upright(&self) -> bool {
+        *self == Self::Classic
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Player4D {
     pub transform: Transform4D,
     pub relative_camera_transform: Transform4D,
-    pub horizontal_orientation: Quat,
-    pub tilt: f32
+    pub horizontal_orientation: Quat,//used by the upright camera scheme
+    pub tilt: f32,
+    pub free_orientation: Mat4,//used by the other ones
+    pub orbit_target: Vec4,
+    pub camera_scheme: CameraScheme4D//follows the one in the options
 }
 impl Player4D {
     pub fn new(_global_data: &GlobalData) -> Self {
@@ -84,13 +140,36 @@ impl Player4D {
                 ..Transform4D::default()
             },
             horizontal_orientation: Quat::IDENTITY,
-            tilt: 0.0
+            tilt: 0.0,
+            free_orientation: Mat4::IDENTITY,
+            orbit_target: Vec4::ZERO,
+            camera_scheme: CameraScheme4D::Classic
         }
     }
 
     pub fn update(&mut self, delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
-        self.do_linear_movement(delta_time, input, global_data);
-        self.do_rotation(delta_time, input, global_data);
+        let camera_scheme = global_data.options.user.input.camera_scheme_4D;
+        if camera_scheme != self.camera_scheme {
+            self.switch_camera_scheme(camera_scheme, global_data);
+        }
+
+        match self.camera_scheme {
+            CameraScheme4D::Classic => {
+                self.do_linear_movement(delta_time, input, global_data);
+                self.do_rotation(delta_time, input, global_data);
+            },
+            CameraScheme4D::FreePlanes => {
+                self.do_linear_movement(delta_time, input, global_data);
+                self.do_free_rotation(delta_time, input, global_data);
+            },
+            CameraScheme4D::Orbit => {
+                let camera_position_before = self.get_camera_world_position();
+                self.do_linear_movement(delta_time, input, global_data);
+                self.orbit_target += self.get_camera_world_position() - camera_position_before;
+                self.do_free_rotation(delta_time, input, global_data);
+                self.place_camera_on_orbit(self.orbit_target, global_data.options.user.input.orbit_distance);
+            }
+        }
     }
 
     pub fn get_trs_matrix(&self) -> AffineTransform4D {
@@ -119,31 +198,122 @@ impl Player4D {
         )
     }
 
+    pub fn get_bookmark(&self) -> PlayerBookmark4D {
+        PlayerBookmark4D {
+            transform: self.transform,
+            horizontal_orientation: self.horizontal_orientation,
+            tilt: self.tilt,
+            camera_scheme: self.camera_scheme,
+            free_orientation: self.free_orientation,
+            orbit_target: self.orbit_target
+        }
+    }
+
+    /* Also restores the camera scheme. Unless the options switch to the same one (see bookmarks::go_to_bookmark),
+       the next update switches back to the scheme in the options from there */
+    pub fn go_to_bookmark(&mut self, bookmark: &PlayerBookmark4D) {
+        self.transform = bookmark.transform;
+        self.horizontal_orientation = bookmark.horizontal_orientation;
+        self.tilt = bookmark.tilt;
+        self.free_orientation = bookmark.free_orientation;
+        self.orbit_target = bookmark.orbit_target;
+        self.camera_scheme = bookmark.camera_scheme;
+        self.relative_camera_transform.orientation = get_relative_camera_orientation(bookmark);
+    }
+
+    //where the camera would be after go_to_bookmark
+    pub fn get_bookmark_camera_transform(&self, bookmark: &PlayerBookmark4D) -> Transform4D {
+        let player_orientation = match bookmark.camera_scheme.is_upright() {
+            true => get_fixed_z_rotation(bookmark.horizontal_orientation),
+            false => bookmark.free_orientation
+        };
+        Transform4D {
+            position: bookmark.transform.position + player_orientation * self.relative_camera_transform.position,
+            orientation: player_orientation * get_relative_camera_orientation(bookmark),
+            ..Transform4D::default()
+        }
+    }
+
+    //moves the whole player, with the camera at its origin. The next update overrides the orientation
+    pub fn place_camera(&mut self, camera_transform: Transform4D) {
+        self.transform = camera_transform;
+        self.relative_camera_transform = Transform4D::IDENTITY;
+    }
+
+    //only for the free camera schemes. Keeps the camera where it is
+    pub fn look_at(&mut self, target: Vec4) {
+        let camera_position = self.get_camera_world_position();
+        let forward = self.free_orientation * Vec4::W;
+        let free_orientation = rotation::get_rotation_between(forward, target - camera_position) * self.free_orientation;
+        self.free_orientation = Rotor4D::from_mat4(free_orientation).normalize().to_mat4();
+        self.transform.orientation = self.free_orientation;
+        self.transform.position += camera_position - self.get_camera_world_position();
+    }
+
+    pub fn do_free_rotation(&mut self, delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
+        let look_delta = -get_look_delta(delta_time, input, global_data);
+        let free_orientation = self.free_orientation * get_free_rotation(look_delta, input, global_data);
+        self.free_orientation = Rotor4D::from_mat4(free_orientation).normalize().to_mat4();//to prevent rounding error build-up
+        self.transform.orientation = self.free_orientation;
+    }
+
+    //only for the free camera schemes
+    pub fn place_camera_on_orbit(&mut self, target: Vec4, distance: f32) {
+        let camera_position = target - self.free_orientation * Vec4::W * distance;
+        self.transform.position += camera_position - self.get_camera_world_position();
+    }
+
     fn do_linear_movement(&mut self, delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
+        let bindings = &global_data.options.user.key_bindings;
         let mut pos_delta = Vec4::ZERO;
-        if input.keyboard_is_pressed(&VirtualKeyCode::Q     ) { pos_delta += Vec4::NEG_X };
-        if input.keyboard_is_pressed(&VirtualKeyCode::E     ) { pos_delta += Vec4::X     };
-        if input.keyboard_is_pressed(&VirtualKeyCode::A     ) { pos_delta += Vec4::NEG_Y };
-        if input.keyboard_is_pressed(&VirtualKeyCode::D     ) { pos_delta += Vec4::Y     };
-        if input.keyboard_is_pressed(&VirtualKeyCode::LShift) { pos_delta += Vec4::NEG_Z };
-        if input.keyboard_is_pressed(&VirtualKeyCode::Space ) { pos_delta += Vec4::Z     };
-        if input.keyboard_is_pressed(&VirtualKeyCode::S     ) { pos_delta += Vec4::NEG_W };
-        if input.keyboard_is_pressed(&VirtualKeyCode::W     ) { pos_delta += Vec4::W     };
+        if bindings.is_pressed(Action::MoveKata, input)     { pos_delta += Vec4::NEG_X };
+        if bindings.is_pressed(Action::MoveAna, input)      { pos_delta += Vec4::X     };
+        if bindings.is_pressed(Action::MoveLeft, input)     { pos_delta += Vec4::NEG_Y };
+        if bindings.is_pressed(Action::MoveRight, input)    { pos_delta += Vec4::Y     };
+        if bindings.is_pressed(Action::MoveDown, input)     { pos_delta += Vec4::NEG_Z };
+        if bindings.is_pressed(Action::MoveUp, input)       { pos_delta += Vec4::Z     };
+        if bindings.is_pressed(Action::MoveBackward, input) { pos_delta += Vec4::NEG_W };
+        if bindings.is_pressed(Action::MoveForward, input)  { pos_delta += Vec4::W     };
+        let gamepad = get_gamepad_axes(input, global_data);
+        pos_delta += Vec4::new(gamepad.right_trigger - gamepad.left_trigger, gamepad.left_stick.x, 0.0, gamepad.left_stick.y) * global_data.options.user.input.gamepad_move_sensitivity;
 
         let pos_delta_world_space = self.transform.orientation * pos_delta;
         self.transform.position += pos_delta_world_space * delta_time * global_data.options.dev.player.walking_speed;
     }
 
-    fn do_rotation(&mut self, _delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
-        let look_delta = -input.mouse_delta() * global_data.options.user.input.mouse_sensitivity;
-        let is_left_button_pressed = input.mouse_is_pressed(&MouseButton::Left);
+    /* Keeps the camera where it is. Going from a free scheme to the upright one returns to the orientation
+       the camera had before, since a free orientation can't generally be represented with horizontal_orientation and tilt. */
+    pub fn switch_camera_scheme(&mut self, camera_scheme: CameraScheme4D, global_data: &GlobalData) {
+        let camera_position = self.get_camera_world_position();
+        let camera_orientation = self.get_camera_world_orientation();
+
+        if camera_scheme.is_upright() {
+            self.transform.orientation = get_fixed_z_rotation(self.horizontal_orientation);
+            self.relative_camera_transform.orientation = rotation::around_xy(self.tilt);
+        }
+        else if self.camera_scheme.is_upright() {
+            self.free_orientation = camera_orientation;
+            self.transform.orientation = camera_orientation;
+            self.relative_camera_transform.orientation = Mat4::IDENTITY;
+        }
+        if camera_scheme == CameraScheme4D::Orbit {
+            self.orbit_target = camera_position + self.free_orientation * Vec4::W * global_data.options.user.input.orbit_distance;
+        }
+
+        self.transform.position += camera_position - self.get_camera_world_position();
+        self.camera_scheme = camera_scheme;
+    }
+
+    fn do_rotation(&mut self, delta_time: f32, input: &InputHandler, global_data: &mut GlobalData) {
+        let look_delta = -get_look_delta(delta_time, input, global_data);
+        let is_alternative_look_pressed = global_data.options.user.key_bindings.is_pressed(Action::AlternativeLook, input);
 
         /* horizontal_delta_local_space is a 3D rotation, which in this case corresponds to 4D as follows:
             Quat around x == Mat4 around xz
             Quat around y == Mat4 around yz
             Quat around z == Mat4 around wz
          */
-        let horizontal_delta_local_space = if is_left_button_pressed {
+        let horizontal_delta_local_space = if is_alternative_look_pressed {
             Quat::from_rotation_x(look_delta.x)
         }
         else {
@@ -152,16 +322,9 @@ impl Player4D {
         };
 
         self.horizontal_orientation = (self.horizontal_orientation * horizontal_delta_local_space).normalize();//normalized to prevent rounding error build-up
-        let r = Mat3::from_quat(self.horizontal_orientation).to_cols_array_2d();
-        let fixed_z_rotation = matrix4x4![//1st index refers to a column
-            r[0][0], r[1][0], 0.0, r[2][0],
-            r[0][1], r[1][1], 0.0, r[2][1],
-            0.0,     0.0,     1.0, 0.0,
-            r[0][2], r[1][2], 0.0, r[2][2]
-        ];
-        self.transform.orientation = fixed_z_rotation;
-
-        if is_left_button_pressed {
+        self.transform.orientation = get_fixed_z_rotation(self.horizontal_orientation);
+
+        if is_alternative_look_pressed {
             self.tilt -= look_delta.y;
             self.tilt = self.tilt.clamp(-TAU / 4.0, TAU / 4.0);
             self.relative_camera_transform.orientation = rotation::around_xy(self.tilt);
@@ -169,6 +332,64 @@ impl Player4D {
     }
 }
 
+fn get_gamepad_axes(input: &InputHandler, global_data: &GlobalData) -> GamepadAxes {
+    input.gamepad_axes().with_deadzone(global_data.options.user.input.gamepad_deadzone)
+}
+
+//in radians, from the mouse and the right stick of the gamepad. Positive y is down, like for the mouse
+fn get_look_delta(delta_time: f32, input: &InputHandler, global_data: &GlobalData) -> Vec2 {
+    let options = &global_data.options.user.input;
+    let right_stick = get_gamepad_axes(input, global_data).right_stick * Vec2::new(1.0, -1.0);
+    input.mouse_delta() * options.mouse_sensitivity + right_stick * options.gamepad_look_sensitivity * delta_time
+}
+
+//rotates in the local space of the camera, with the same directions as the upright scheme
+fn get_free_rotation(look_delta: Vec2, input: &InputHandler, global_data: &GlobalData) -> Mat4 {
+    let bindings = &global_data.options.user.key_bindings;
+    if bindings.is_pressed(Action::RollLook, input) {
+        rotation::around_xw(look_delta.x) * rotation::around_yw(look_delta.y)
+    }
+    else if bindings.is_pressed(Action::AlternativeLook, input) {
+        get_fixed_z_rotation(Quat::from_rotation_z(look_delta.x) * Quat::from_rotation_y(look_delta.y))
+    }
+    else {
+        get_fixed_z_rotation(Quat::from_rotation_x(look_delta.x)) * rotation::around_xy(-look_delta.y)
+    }
+}
+
+//the upright scheme tilts the camera relative to the player, the free ones rotate the whole player
+fn get_relative_camera_orientation(bookmark: &PlayerBookmark4D) -> Mat4 {
+    match bookmark.camera_scheme.is_upright() {
+        true => rotation::around_xy(bookmark.tilt),
+        false => Mat4::IDENTITY
+    }
+}
+
+//the 3D rotation of horizontal_orientation, acting on x, y and w
+fn get_fixed_z_rotation(horizontal_orientation: Quat) -> Mat4 {
+    let r = Mat3::from_quat(horizontal_orientation).to_cols_array_2d();
+    matrix4x4![//1st index refers to a column
+        r[0][0], r[1][0], 0.0, r[2][0],
+        r[0][1], r[1][1], 0.0, r[2][1],
+        0.0,     0.0,     1.0, 0.0,
+        r[0][2], r[1][2], 0.0, r[2][2]
+    ]
+}
+
+//bookmarks from before the free camera schemes are upright, so they get the defaults
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PlayerBookmark4D {
+    pub transform: Transform4D,
+    pub horizontal_orientation: Quat,
+    pub tilt: f32,
+    #[serde(default)]
+    pub camera_scheme: CameraScheme4D,
+    #[serde(default)]
+    pub free_orientation: Mat4,
+    #[serde(default)]
+    pub orbit_target: Vec4
+}
+
 //affine transformation, so doesn't give W (depth divider)
 pub fn player_projection_matrix_3D(global_data: &GlobalData) -> AffineTransform3D {
     let y = 1.0 / f32::tan(global_data.options.dev.camera.fov.to_radians() * 0.5);
@@ -210,4 +431,23 @@ pub fn player_projection_matrix_4D(global_data: &GlobalData) -> AffineTransform4
         ],
         translation: Vec4::new(0.0, 0.0, 0.0, -(2.0*near*far)/(far-near))
     }
-}
\ No newline at end of file
+}
+
+//the camera that looks at the volume the 4D camera projects onto, which is [-1, 1]^3 for everything in its field of view
+pub fn projection_4D_volume_view_matrix(global_data: &GlobalData) -> AffineTransform3D {
+    let options = &global_data.options.user.graphics;
+
+    //without rotation, the volume is seen like the slice is: y is right, z is up and x (the degenerate axis) is forward
+    let axes = matrix3x3![
+        0.0, 1.0, 0.0,
+        0.0, 0.0, 1.0,
+        1.0, 0.0, 0.0
+    ];
+    let orbit = rotation::around_y(options.projection_4D_view_pitch.to_radians())
+        * rotation::around_z(options.projection_4D_view_yaw.to_radians());
+
+    AffineTransform3D {
+        linear_transform: axes * orbit,
+        translation: Vec3::new(0.0, 0.0, options.projection_4D_view_distance)
+    }
+}
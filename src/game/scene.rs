@@ -0,0 +1,229 @@
+use super::entity::{EntityId, ComponentStorage, Collider3D, Collider4D, Behavior3D, Behavior4D};
+use super::animation::Animation4D;
+use super::light::{Light3D, Light4D};
+use super::transform::{Transform3D, Transform4D};
+use crate::renderer::mesh::{StaticUploadedMesh3D, StaticUploadedMesh4D};
+use crate::renderer::shading::dynamic_material::DynamicMaterial;
+use std::rc::Rc;
+
+/* All objects of a world are entities. An entity is just an id, and everything else is an optional component.
+   An entity is rendered if it has a transform, a mesh and a material. */
+pub struct Scene3D {
+    entity_count: usize,
+    pub transforms: ComponentStorage<Transform3D>,
+    pub meshes: ComponentStorage<Rc<StaticUploadedMesh3D>>,
+    pub materials: ComponentStorage<DynamicMaterial>,
+    pub colliders: ComponentStorage<Collider3D>,
+    pub behaviors: ComponentStorage<Box<dyn Behavior3D>>,
+    pub lights: ComponentStorage<Light3D>,
+    despawned_during_update: Option<Vec<EntityId>>//the behaviors are out of the scene then, so their removal is deferred
+}
+pub struct Scene4D {
+    entity_count: usize,
+    pub transforms: ComponentStorage<Transform4D>,
+    pub meshes: ComponentStorage<Rc<StaticUploadedMesh4D>>,
+    pub materials: ComponentStorage<DynamicMaterial>,
+    pub colliders: ComponentStorage<Collider4D>,
+    pub behaviors: ComponentStorage<Box<dyn Behavior4D>>,
+    pub animations: ComponentStorage<Animation4D>,
+    pub lights: ComponentStorage<Light4D>,
+    despawned_during_update: Option<Vec<EntityId>>//the behaviors are out of the scene then, so their removal is deferred
+}
+
+impl Scene3D {
+    pub fn new() -> Self {
+        Self {
+            entity_count: 0,
+            transforms: ComponentStorage::new(),
+            meshes: ComponentStorage::new(),
+            materials: ComponentStorage::new(),
+            colliders: ComponentStorage::new(),
+            behaviors: ComponentStorage::new(),
+            lights: ComponentStorage::new(),
+            despawned_during_update: None
+        }
+    }
+
+    //ids are never reused
+    pub fn spawn(&mut self) -> EntityId {
+        self.entity_count += 1;
+        EntityId(self.entity_count - 1)
+    }
+
+    pub fn despawn(&mut self, entity: EntityId) {
+        self.transforms.remove(entity);
+        self.meshes.remove(entity);
+        self.materials.remove(entity);
+        self.colliders.remove(entity);
+        self.behaviors.remove(entity);
+        self.lights.remove(entity);
+        if let Some(despawned) = &mut self.despawned_during_update {
+            despawned.push(entity);
+        }
+    }
+
+    fn was_despawned_during_update(&self, entity: EntityId) -> bool {
+        self.despawned_during_update.as_ref().is_some_and(|despawned| despawned.contains(&entity))
+    }
+
+    //behavior system
+    pub fn update(&mut self, delta_time: f32) {
+        let mut behaviors = std::mem::take(&mut self.behaviors);
+        self.despawned_during_update = Some(Vec::new());
+        for (entity, behavior) in behaviors.iter_mut() {
+            if !self.was_despawned_during_update(entity) {
+                behavior.update(entity, self, delta_time);
+            }
+        }
+
+        //keep behaviors that were added during the update
+        for (entity, behavior) in std::mem::replace(&mut self.behaviors, behaviors).into_iter() {
+            self.behaviors.insert(entity, behavior);
+        }
+        for entity in self.despawned_during_update.take().unwrap_or_default() {
+            self.behaviors.remove(entity);
+        }
+    }
+}
+impl Scene4D {
+    pub fn new() -> Self {
+        Self {
+            entity_count: 0,
+            transforms: ComponentStorage::new(),
+            meshes: ComponentStorage::new(),
+            materials: ComponentStorage::new(),
+            colliders: ComponentStorage::new(),
+            behaviors: ComponentStorage::new(),
+            animations: ComponentStorage::new(),
+            lights: ComponentStorage::new(),
+            despawned_during_update: None
+        }
+    }
+
+    //ids are never reused
+    pub fn spawn(&mut self) -> EntityId {
+        self.entity_count += 1;
+        EntityId(self.entity_count - 1)
+    }
+
+    pub fn despawn(&mut self, entity: EntityId) {
+        self.transforms.remove(entity);
+        self.meshes.remove(entity);
+        self.materials.remove(entity);
+        self.colliders.remove(entity);
+        self.behaviors.remove(entity);
+        self.animations.remove(entity);
+        self.lights.remove(entity);
+        if let Some(despawned) = &mut self.despawned_during_update {
+            despawned.push(entity);
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.update_behaviors(delta_time);
+        self.update_animations(delta_time);
+    }
+
+    fn was_despawned_during_update(&self, entity: EntityId) -> bool {
+        self.despawned_during_update.as_ref().is_some_and(|despawned| despawned.contains(&entity))
+    }
+
+    //behavior system
+    fn update_behaviors(&mut self, delta_time: f32) {
+        let mut behaviors = std::mem::take(&mut self.behaviors);
+        self.despawned_during_update = Some(Vec::new());
+        for (entity, behavior) in behaviors.iter_mut() {
+            if !self.was_despawned_during_update(entity) {
+                behavior.update(entity, self, delta_time);
+            }
+        }
+
+        //keep behaviors that were added during the update
+        for (entity, behavior) in std::mem::replace(&mut self.behaviors, behaviors).into_iter() {
+            self.behaviors.insert(entity, behavior);
+        }
+        for entity in self.despawned_during_update.take().unwrap_or_default() {
+            self.behaviors.remove(entity);
+        }
+    }
+
+    //animation system
+    fn update_animations(&mut self, delta_time: f32) {
+        for (entity, animation) in self.animations.iter_mut() {
+            animation.advance(delta_time);
+            self.transforms.insert(entity, animation.get_transform());
+        }
+    }
+}
+
+impl Default for Scene3D {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Default for Scene4D {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    //counts its updates, and despawns its target on the first one
+    struct Despawner {
+        target: EntityId,
+        update_count: Rc<Cell<u32>>
+    }
+    impl Behavior3D for Despawner {
+        fn update(&mut self, _entity: EntityId, scene: &mut Scene3D, _delta_time: f32) {
+            self.update_count.set(self.update_count.get() + 1);
+            scene.despawn(self.target);
+        }
+    }
+    impl Behavior4D for Despawner {
+        fn update(&mut self, _entity: EntityId, scene: &mut Scene4D, _delta_time: f32) {
+            self.update_count.set(self.update_count.get() + 1);
+            scene.despawn(self.target);
+        }
+    }
+
+    #[test]
+    fn behavior_can_despawn_its_own_entity() {
+        let update_count = Rc::new(Cell::new(0));
+        let mut scene_3D = Scene3D::new();
+        let entity = scene_3D.spawn();
+        scene_3D.behaviors.insert(entity, Box::new(Despawner { target: entity, update_count: update_count.clone() }));
+        let mut scene_4D = Scene4D::new();
+        let entity = scene_4D.spawn();
+        scene_4D.behaviors.insert(entity, Box::new(Despawner { target: entity, update_count: update_count.clone() }));
+
+        for _ in 0..3 {
+            scene_3D.update(0.1);
+            scene_4D.update(0.1);
+        }
+        assert_eq!(update_count.get(), 2);
+        assert!(scene_3D.behaviors.iter().next().is_none());
+        assert!(scene_4D.behaviors.iter().next().is_none());
+    }
+
+    #[test]
+    fn behavior_of_despawned_entity_stops_in_the_same_update() {
+        let despawner_updates = Rc::new(Cell::new(0));
+        let target_updates = Rc::new(Cell::new(0));
+        let mut scene = Scene4D::new();
+        let despawner = scene.spawn();
+        let target = scene.spawn();
+        let other = scene.spawn();
+        scene.behaviors.insert(despawner, Box::new(Despawner { target, update_count: despawner_updates.clone() }));
+        scene.behaviors.insert(target, Box::new(Despawner { target: other, update_count: target_updates.clone() }));
+
+        scene.update(0.1);
+        scene.update(0.1);
+        assert_eq!(despawner_updates.get(), 2);
+        assert_eq!(target_updates.get(), 0);
+        assert!(!scene.behaviors.contains(target));
+    }
+}
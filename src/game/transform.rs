@@ -1,8 +1,10 @@
 pub mod affine_transform;
 pub mod rotation;
+pub mod rotor;
 
 pub use affine_transform::{AffineTransform3D, AffineTransform4D};
 use glam::{Vec3, Mat3, Vec4, Mat4};
+use serde::{Serialize, Deserialize};
 
 /* coordinate conventions for view space:
     3D:
@@ -17,13 +19,13 @@ use glam::{Vec3, Mat3, Vec4, Mat4};
         W: forward
 */
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Transform3D {
     pub position: Vec3,
     pub orientation: Mat3,//not a quaternion, because matrices are easier to generalize to 4D
     pub scale: Vec3
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Transform4D {
     pub position: Vec4,
     pub orientation: Mat4,
@@ -1,5 +1,6 @@
-use glam::{Mat3, Mat4};
+use glam::{Mat3, Mat4, Quat, Vec4};
 use super::{matrix3x3, matrix4x4};
+use super::rotor::Rotor4D;
 use rand::Rng;
 use std::f32::consts::TAU;
 
@@ -111,3 +112,180 @@ pub fn random_4D_nonuniform<R: Rng>(rng: &mut R) -> Mat4 {
     around_yw(rng.gen_range(0.0..TAU)) *
     around_zw(rng.gen_range(0.0..TAU))
 }
+
+//distributed uniformly (by the Haar measure), since unit quaternions cover SO(3) uniformly
+pub fn random_3D_uniform<R: Rng>(rng: &mut R) -> Mat3 {
+    Mat3::from_quat(random_unit_quaternion(rng))
+}
+//every rotation in SO(4) is given by exactly two pairs of unit quaternions, so independent uniform pairs are uniform in SO(4)
+pub fn random_4D_uniform<R: Rng>(rng: &mut R) -> Mat4 {
+    Rotor4D {
+        left: random_unit_quaternion(rng),
+        right: random_unit_quaternion(rng)
+    }.to_mat4()
+}
+
+//uniform on the unit 3-sphere
+//Shoemake, "Uniform random rotations", Graphics Gems III
+fn random_unit_quaternion<R: Rng>(rng: &mut R) -> Quat {
+    let u1: f32 = rng.gen_range(0.0..1.0);
+    let (sin2, cos2) = rng.gen_range(0.0..TAU).sin_cos();
+    let (sin3, cos3) = rng.gen_range(0.0..TAU).sin_cos();
+    let a = (1.0 - u1).sqrt();
+    let b = u1.sqrt();
+    Quat::from_xyzw(a * sin2, a * cos2, b * sin3, b * cos3)
+}
+
+//the smallest rotation that turns the direction of from into the direction of to
+pub fn get_rotation_between(from: Vec4, to: Vec4) -> Mat4 {
+    let a = from.normalize();
+    let to = to.normalize();
+    let cos = a.dot(to).clamp(-1.0, 1.0);
+    let perpendicular = to - a * cos;
+    let sin = perpendicular.length();
+    let b = match (sin > 1e-6, cos > 0.0) {
+        (true, _) => perpendicular / sin,
+        (false, true) => return Mat4::IDENTITY,
+        (false, false) => {
+            //opposite, so any plane containing a will do
+            let axis = [Vec4::X, Vec4::Y, Vec4::Z, Vec4::W].into_iter().min_by(|p, q| p.dot(a).abs().total_cmp(&q.dot(a).abs())).unwrap();
+            (axis - a * axis.dot(a)).normalize()
+        }
+    };
+
+    //v + (cos - 1)((v.a)a + (v.b)b) + sin((v.a)b - (v.b)a), in the plane of a and b
+    let rotate = |v: Vec4| v + (cos - 1.0) * (v.dot(a) * a + v.dot(b) * b) + sin * (v.dot(a) * b - v.dot(b) * a);
+    Mat4::from_cols(rotate(Vec4::X), rotate(Vec4::Y), rotate(Vec4::Z), rotate(Vec4::W))
+}
+
+//named like the around_* functions, that is, after the plane that stays fixed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RotationPlane4D {
+    XY,
+    XZ,
+    XW,
+    YZ,
+    YW,
+    ZW
+}
+impl RotationPlane4D {
+    pub const ALL: [Self; 6] = [Self::XY, Self::XZ, Self::XW, Self::YZ, Self::YW, Self::ZW];
+
+    pub fn rotation(&self, angle: f32) -> Mat4 {
+        match self {
+            Self::XY => around_xy(angle),
+            Self::XZ => around_xz(angle),
+            Self::XW => around_xw(angle),
+            Self::YZ => around_yz(angle),
+            Self::YW => around_yw(angle),
+            Self::ZW => around_zw(angle)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+    use rand::{rngs::SmallRng, SeedableRng};
+    use std::f32::consts::PI;
+
+    const SAMPLE_COUNT: usize = 20000;
+
+    fn samples_3D(generate: fn(&mut SmallRng) -> Mat3) -> Vec<Mat3> {
+        let mut rng = SmallRng::seed_from_u64(3);
+        (0..SAMPLE_COUNT).map(|_| generate(&mut rng)).collect()
+    }
+    fn samples_4D(generate: fn(&mut SmallRng) -> Mat4) -> Vec<Mat4> {
+        let mut rng = SmallRng::seed_from_u64(4);
+        (0..SAMPLE_COUNT).map(|_| generate(&mut rng)).collect()
+    }
+
+    //about 5 standard deviations of the mean of SAMPLE_COUNT samples with a variance of at most 1
+    fn assert_mean_is_zero(mean: f32) {
+        assert!(mean.abs() < 5.0 / (SAMPLE_COUNT as f32).sqrt(), "mean {mean} is not 0");
+    }
+
+    fn trace_3D(matrix: &Mat3) -> f32 {
+        matrix.x_axis.x + matrix.y_axis.y + matrix.z_axis.z
+    }
+    fn trace_4D(matrix: &Mat4) -> f32 {
+        matrix.x_axis.x + matrix.y_axis.y + matrix.z_axis.z + matrix.w_axis.w
+    }
+
+    /* Pearson's chi-square statistic of the rotation angles against the Haar density (1 - cos θ) / π,
+       whose cumulative distribution is (θ - sin θ) / π */
+    fn angle_chi_square(rotations: &[Mat3]) -> f32 {
+        const BIN_COUNT: usize = 20;
+        let mut counts = [0usize; BIN_COUNT];
+        for rotation in rotations {
+            let angle = ((trace_3D(rotation) - 1.0) / 2.0).clamp(-1.0, 1.0).acos();
+            counts[((angle / PI * BIN_COUNT as f32) as usize).min(BIN_COUNT - 1)] += 1;
+        }
+
+        let cumulative = |angle: f32| (angle - angle.sin()) / PI;
+        counts.iter().enumerate()
+            .map(|(bin, count)| {
+                let [start, end] = [bin, bin + 1].map(|edge| edge as f32 / BIN_COUNT as f32 * PI);
+                let expected = rotations.len() as f32 * (cumulative(end) - cumulative(start));
+                (*count as f32 - expected).powi(2) / expected
+            })
+            .sum()
+    }
+    //for 19 degrees of freedom, exceeded with a probability of 0.001
+    const CHI_SQUARE_CRITICAL_VALUE: f32 = 43.82;
+
+    #[test]
+    fn uniform_rotations_are_orthonormal() {
+        for rotation in samples_3D(random_3D_uniform) {
+            assert!((rotation.transpose() * rotation).abs_diff_eq(Mat3::IDENTITY, 1e-5));
+            assert!((rotation.determinant() - 1.0).abs() < 1e-5);
+        }
+        for rotation in samples_4D(random_4D_uniform) {
+            assert!((rotation.transpose() * rotation).abs_diff_eq(Mat4::IDENTITY, 1e-5));
+            assert!((rotation.determinant() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn uniform_unit_quaternions_are_centered() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let quaternions: Vec<Quat> = (0..SAMPLE_COUNT).map(|_| random_unit_quaternion(&mut rng)).collect();
+        assert!(quaternions.iter().all(|quaternion| quaternion.is_normalized()));
+        let mean = quaternions.iter().map(|quaternion| Vec4::from(*quaternion)).sum::<Vec4>() / SAMPLE_COUNT as f32;
+        mean.to_array().into_iter().for_each(assert_mean_is_zero);
+    }
+
+    #[test]
+    fn uniform_rotations_move_the_axes_anywhere() {
+        let rotations = samples_3D(random_3D_uniform);
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            let mean = rotations.iter().map(|rotation| *rotation * axis).sum::<Vec3>() / SAMPLE_COUNT as f32;
+            mean.to_array().into_iter().for_each(assert_mean_is_zero);
+        }
+        let rotations = samples_4D(random_4D_uniform);
+        for axis in [Vec4::X, Vec4::Y, Vec4::Z, Vec4::W] {
+            let mean = rotations.iter().map(|rotation| *rotation * axis).sum::<Vec4>() / SAMPLE_COUNT as f32;
+            mean.to_array().into_iter().for_each(assert_mean_is_zero);
+        }
+    }
+
+    #[test]
+    fn uniform_rotations_have_a_mean_trace_of_zero() {
+        assert_mean_is_zero(samples_3D(random_3D_uniform).iter().map(trace_3D).sum::<f32>() / SAMPLE_COUNT as f32);
+        assert_mean_is_zero(samples_4D(random_4D_uniform).iter().map(trace_4D).sum::<f32>() / SAMPLE_COUNT as f32);
+    }
+
+    #[test]
+    fn uniform_rotation_angles_follow_the_haar_density() {
+        let chi_square = angle_chi_square(&samples_3D(random_3D_uniform));
+        assert!(chi_square < CHI_SQUARE_CRITICAL_VALUE, "chi-square {chi_square}");
+    }
+
+    //makes sure the chi-square test can tell
+    #[test]
+    fn nonuniform_rotation_angles_dont_follow_the_haar_density() {
+        let chi_square = angle_chi_square(&samples_3D(random_3D_nonuniform));
+        assert!(chi_square > CHI_SQUARE_CRITICAL_VALUE, "chi-square {chi_square}");
+    }
+}
@@ -0,0 +1,327 @@
+use glam::{Vec3, Mat3, Vec4, Mat4, Quat};
+use super::rotation::RotationPlane4D;
+use std::ops::{Mul, MulAssign};
+
+// References:
+// https://en.wikipedia.org/wiki/Rotations_in_4-dimensional_Euclidean_space#Algebra_of_4D_rotations
+// https://en.wikipedia.org/wiki/Quaternions_and_spatial_rotation
+
+/* Coefficients of a bivector (a plane of rotation, scaled by the angle).
+   Unlike RotationPlane4D, which is named after the plane that stays fixed, these are named after the plane
+   that rotates, with a positive coefficient rotating the first axis toward the second one. */
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Bivector4D {
+    pub xy: f32,
+    pub xz: f32,
+    pub xw: f32,
+    pub yz: f32,
+    pub yw: f32,
+    pub zw: f32
+}
+impl Bivector4D {
+    pub const ZERO: Self = Self { xy: 0.0, xz: 0.0, xw: 0.0, yz: 0.0, yw: 0.0, zw: 0.0 };
+
+    //a bivector whose exponential is plane.rotation(angle)
+    pub fn from_plane(plane: RotationPlane4D, angle: f32) -> Self {
+        match plane {
+            RotationPlane4D::XY => Self { zw: angle, ..Self::ZERO },
+            RotationPlane4D::XZ => Self { yw: angle, ..Self::ZERO },
+            RotationPlane4D::XW => Self { yz: angle, ..Self::ZERO },
+            RotationPlane4D::YZ => Self { xw: angle, ..Self::ZERO },
+            RotationPlane4D::YW => Self { xz: angle, ..Self::ZERO },
+            RotationPlane4D::ZW => Self { xy: angle, ..Self::ZERO }
+        }
+    }
+
+    pub fn exp(&self) -> Rotor4D {
+        Rotor4D::from_bivector(*self)
+    }
+
+    /* The generator v -> a*v + v*b, with pure quaternions a and b, is split into
+       its self-dual (left) and anti-self-dual (right) halves. */
+    fn to_left_right(self) -> (Vec3, Vec3) {
+        let left = 0.5 * Vec3::new(self.yz - self.xw, -self.xz - self.yw, self.xy - self.zw);
+        let right = 0.5 * Vec3::new(-self.yz - self.xw, self.xz - self.yw, -self.xy - self.zw);
+        (left, right)
+    }
+
+    fn from_left_right(left: Vec3, right: Vec3) -> Self {
+        Self {
+            xy: left.z - right.z,
+            xz: right.y - left.y,
+            xw: -left.x - right.x,
+            yz: left.x - right.x,
+            yw: -left.y - right.y,
+            zw: -left.z - right.z
+        }
+    }
+}
+impl Mul<f32> for Bivector4D {
+    type Output = Self;
+
+    fn mul(self, scalar: f32) -> Self {
+        Self {
+            xy: self.xy * scalar,
+            xz: self.xz * scalar,
+            xw: self.xw * scalar,
+            yz: self.yz * scalar,
+            yw: self.yw * scalar,
+            zw: self.zw * scalar
+        }
+    }
+}
+impl std::ops::Add for Bivector4D {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            xy: self.xy + other.xy,
+            xz: self.xz + other.xz,
+            xw: self.xw + other.xw,
+            yz: self.yz + other.yz,
+            yw: self.yw + other.yw,
+            zw: self.zw + other.zw
+        }
+    }
+}
+
+/* A rotation in 4D as a pair of unit quaternions ("double quaternion"), acting on a vector v as left * v * right,
+   where v = (x, y, z, w) is read as the quaternion xi + yj + zk + w.
+   (left, right) and (-left, -right) are the same rotation. */
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rotor4D {
+    pub left: Quat,
+    pub right: Quat
+}
+impl Rotor4D {
+    pub const IDENTITY: Self = Self {
+        left: Quat::IDENTITY,
+        right: Quat::IDENTITY
+    };
+
+    pub fn from_plane(plane: RotationPlane4D, angle: f32) -> Self {
+        Self::from_bivector(Bivector4D::from_plane(plane, angle))
+    }
+
+    pub fn from_bivector(bivector: Bivector4D) -> Self {
+        let (left, right) = bivector.to_left_right();
+        Self {
+            left: quat_exp(left),
+            right: quat_exp(right)
+        }
+    }
+
+    //the shortest of the two equivalent logarithms
+    pub fn log(&self) -> Bivector4D {
+        let candidates = [(self.left, self.right), (-self.left, -self.right)]
+            .map(|(left, right)| (quat_log(left), quat_log(right)));
+        let (left, right) = if candidates[0].0.length_squared() + candidates[0].1.length_squared()
+            <= candidates[1].0.length_squared() + candidates[1].1.length_squared() {
+            candidates[0]
+        } else {
+            candidates[1]
+        };
+        Bivector4D::from_left_right(left, right)
+    }
+
+    //the matrix has to be a rotation (orthonormal, with a determinant of 1)
+    pub fn from_mat4(matrix: Mat4) -> Self {
+        //the image of 1 (that is, of w) is left * right
+        let product = Quat::from_vec4(matrix.w_axis);
+
+        //conjugating by left rotates the pure quaternions, which is a 3D rotation
+        let conjugated = |column: Vec4| (Quat::from_vec4(column) * product.conjugate()).xyz();
+        let left_rotation = Mat3::from_cols(
+            conjugated(matrix.x_axis),
+            conjugated(matrix.y_axis),
+            conjugated(matrix.z_axis)
+        );
+        let left = Quat::from_mat3(&left_rotation).normalize();
+        let right = (left.conjugate() * product).normalize();
+
+        Self { left, right }
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        Mat4::from_cols(
+            self.rotate(Vec4::X),
+            self.rotate(Vec4::Y),
+            self.rotate(Vec4::Z),
+            self.rotate(Vec4::W)
+        )
+    }
+
+    pub fn rotate(&self, vector: Vec4) -> Vec4 {
+        Vec4::from(self.left * Quat::from_vec4(vector) * self.right)
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            left: self.left.conjugate(),
+            right: self.right.conjugate()
+        }
+    }
+
+    //undoes floating point drift after many compositions
+    pub fn normalize(&self) -> Self {
+        Self {
+            left: self.left.normalize(),
+            right: self.right.normalize()
+        }
+    }
+
+    /* Rotates at a constant angular velocity from self (t = 0) to other (t = 1), along the shortest path.
+       The halves can't be slerped independently, because flipping the sign of only one of them
+       gives a different rotation, so the sign is chosen for both of them at once. */
+    pub fn slerp(&self, other: Self, t: f32) -> Self {
+        let other = if self.left.dot(other.left) + self.right.dot(other.right) < 0.0 {
+            Self { left: -other.left, right: -other.right }
+        } else {
+            other
+        };
+
+        Self {
+            left: quat_slerp_unflipped(self.left, other.left, t),
+            right: quat_slerp_unflipped(self.right, other.right, t)
+        }
+    }
+}
+//composes like matrices: (a * b).to_mat4() == a.to_mat4() * b.to_mat4()
+impl Mul for Rotor4D {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self {
+            left: self.left * other.left,
+            right: other.right * self.right
+        }
+    }
+}
+impl MulAssign for Rotor4D {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+impl Mul<Vec4> for Rotor4D {
+    type Output = Vec4;
+
+    fn mul(self, vector: Vec4) -> Vec4 {
+        self.rotate(vector)
+    }
+}
+impl From<Rotor4D> for Mat4 {
+    fn from(rotor: Rotor4D) -> Self {
+        rotor.to_mat4()
+    }
+}
+
+//exp of the pure quaternion with vector part v
+fn quat_exp(vector: Vec3) -> Quat {
+    Quat::from_scaled_axis(2.0 * vector)
+}
+fn quat_log(quaternion: Quat) -> Vec3 {
+    0.5 * quaternion.to_scaled_axis()
+}
+
+//glam's slerp negates one of the quaternions to take the shorter path, which would break the pairing
+fn quat_slerp_unflipped(from: Quat, to: Quat, t: f32) -> Quat {
+    let (axis, angle) = (from.conjugate() * to).normalize().to_axis_angle();
+    (from * Quat::from_axis_angle(axis, angle * t)).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::rotation;
+    use rand::{rngs::SmallRng, SeedableRng, Rng};
+    use std::f32::consts::PI;
+
+    const TOLERANCE: f32 = 1e-4;
+    const ANGLES: [f32; 6] = [-2.5, -0.7, 0.0, 0.3, 1.2, 3.0];
+
+    fn random_rotors(count: usize) -> Vec<Rotor4D> {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let mut random_angle = || rng.gen_range(-PI..PI);
+        (0..count)
+            .map(|_| Bivector4D {
+                xy: random_angle(),
+                xz: random_angle(),
+                xw: random_angle(),
+                yz: random_angle(),
+                yw: random_angle(),
+                zw: random_angle()
+            }.exp())
+            .collect()
+    }
+
+    fn assert_matrices_equal(a: Mat4, b: Mat4) {
+        assert!(a.abs_diff_eq(b, TOLERANCE), "{a} != {b}");
+    }
+
+    //(left, right) and (-left, -right) are the same rotation
+    fn assert_rotors_equal(a: Rotor4D, b: Rotor4D) {
+        let equal = |b: Rotor4D| a.left.abs_diff_eq(b.left, TOLERANCE) && a.right.abs_diff_eq(b.right, TOLERANCE);
+        assert!(equal(b) || equal(Rotor4D { left: -b.left, right: -b.right }), "{a:?} != ±{b:?}");
+    }
+
+    #[test]
+    fn from_plane_matches_the_rotation_matrices() {
+        for plane in RotationPlane4D::ALL {
+            for angle in ANGLES {
+                assert_matrices_equal(Rotor4D::from_plane(plane, angle).to_mat4(), plane.rotation(angle));
+            }
+        }
+    }
+
+    #[test]
+    fn from_mat4_inverts_to_mat4() {
+        for rotor in random_rotors(100) {
+            assert_rotors_equal(Rotor4D::from_mat4(rotor.to_mat4()), rotor);
+        }
+    }
+
+    #[test]
+    fn from_bivector_inverts_log() {
+        for rotor in random_rotors(100) {
+            assert_rotors_equal(Rotor4D::from_bivector(rotor.log()), rotor);
+        }
+    }
+
+    #[test]
+    fn multiplication_composes_like_matrices() {
+        let rotors = random_rotors(50);
+        for (a, b) in rotors.iter().zip(rotors.iter().rev()) {
+            assert_matrices_equal((*a * *b).to_mat4(), a.to_mat4() * b.to_mat4());
+            assert_matrices_equal((*a * *b).inverse().to_mat4(), b.inverse().to_mat4() * a.inverse().to_mat4());
+        }
+        assert_matrices_equal(
+            (Rotor4D::from_plane(RotationPlane4D::XY, 0.4) * Rotor4D::from_plane(RotationPlane4D::YW, 1.1)).to_mat4(),
+            rotation::around_xy(0.4) * rotation::around_yw(1.1)
+        );
+    }
+
+    #[test]
+    fn slerp_hits_the_endpoints() {
+        let rotors = random_rotors(50);
+        for (a, b) in rotors.iter().zip(rotors.iter().rev()) {
+            assert_rotors_equal(a.slerp(*b, 0.0), *a);
+            assert_rotors_equal(a.slerp(*b, 1.0), *b);
+        }
+    }
+
+    //the other pair for the same end rotation would go around the long way
+    #[test]
+    fn slerp_takes_the_short_path() {
+        for rotor in random_rotors(50) {
+            for plane in RotationPlane4D::ALL {
+                let step = Rotor4D::from_plane(plane, 0.8);
+                let end = rotor * step;
+                let flipped_end = Rotor4D { left: -end.left, right: -end.right };
+                let halfway = rotor * Rotor4D::from_plane(plane, 0.4);
+
+                assert_matrices_equal(rotor.slerp(end, 0.5).to_mat4(), halfway.to_mat4());
+                assert_matrices_equal(rotor.slerp(flipped_end, 0.5).to_mat4(), halfway.to_mat4());
+            }
+        }
+    }
+}
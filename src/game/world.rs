@@ -1,24 +1,37 @@
 use super::player::{Player3D, Player4D};
+use super::camera_path::CameraPathPlayback;
+use super::bvh;
+use super::inspection::Inspection4D;
 use crate::global_data::GlobalData;
-use crate::renderer::mesh;
+use crate::options::SceneOptions;
+use crate::renderer::{mesh, GlContext};
 use std::time::Instant;
 use glam::{Mat3, Vec3, Mat4, Vec4, swizzles::*, Vec2};
-use std::vec::Vec;
 use super::transform::{Transform3D, Transform4D, rotation, switch_matrix3_columns, switch_matrix4_columns};
-use crate::renderer::renderable_object::{RenderableObject3D, RenderableObject4D};
 use crate::renderer::shading::materials;
+use super::scene::{Scene3D, Scene4D};
+use super::entity::{EntityId, Collider3D, Collider4D};
+use super::light::{Light3D, Light4D};
+use super::animation::{Animation4D, AngularVelocity4D, Keyframe4D, Interpolation, PlaybackMode, get_rotor_from_angles};
+use super::transform::rotation::RotationPlane4D;
+use std::f32::consts::TAU;
 use rand::{rngs::SmallRng, SeedableRng, Rng};
+use std::rc::Rc;
 
 pub struct Multiverse {
     pub world_3D: World3D,
     pub world_4D: World4D,
+    pub camera_path: Option<CameraPathPlayback>,//drives the 4D camera instead of the player while playing
+    pub inspection: Option<Inspection4D>,
     pub last_update_time: Instant
 }
 impl Multiverse {
-    pub fn new(global_data: &GlobalData, display: &glium::Display) -> Self {
+    pub fn new(global_data: &GlobalData, display: &GlContext) -> Self {
         Self {
             world_3D: World3D::new(global_data, display),
             world_4D: World4D::new(global_data, display),
+            camera_path: None,
+            inspection: None,
             last_update_time: Instant::now()
         }
     }
@@ -26,48 +39,90 @@ impl Multiverse {
 
 pub struct World3D {
     pub player: Player3D,
-    pub static_scene: Vec<RenderableObject3D<materials::SingleColorMaterial>>,
-    pub floor: RenderableObject3D<materials::ChessboardMaterial>
+    pub scene: Scene3D
 }
 impl World3D {
-    pub fn new(global_data: &GlobalData, display: &glium::Display) -> Self {
+    pub fn new(global_data: &GlobalData, display: &GlContext) -> Self {
+        let mut scene = Scene3D::new();
+        spawn_static_scene_objects_3D(&mut scene, &global_data.options.dev.scene, display);
+        spawn_floor_3D(&mut scene, display);
+        spawn_lights_3D(&mut scene);
+        spawn_material_showcase_3D(&mut scene, &global_data.options.dev.scene, display);
+
         Self {
             player: Player3D::new(global_data),
-            static_scene: get_static_scene_objects_3D(display),
-            floor: get_floor_3D(display)
+            scene
         }
     }
 }
 pub struct World4D {
     pub player: Player4D,
-    pub static_scene: Vec<RenderableObject4D<materials::SingleColorMaterial>>,
-    pub floor: RenderableObject4D<materials::ChessboardMaterial>
+    pub scene: Scene4D,
+    pub scene_bvh: bvh::SceneBvh4D,//for picking, kept in sync by update_scene
+    pub floor: EntityId//not inspectable, since it has no center to orbit
 }
 impl World4D {
-    pub fn new(global_data: &GlobalData, display: &glium::Display) -> Self {
+    pub fn new(global_data: &GlobalData, display: &GlContext) -> Self {
+        let mut scene = Scene4D::new();
+        spawn_static_scene_objects_4D(&mut scene, &global_data.options.dev.scene, display);
+        spawn_animated_objects_4D(&mut scene, display);
+        let floor = spawn_floor_4D(&mut scene, display);
+        spawn_lights_4D(&mut scene);
+        spawn_material_showcase_4D(&mut scene, &global_data.options.dev.scene, display);
+
         Self {
             player: Player4D::new(global_data),
-            static_scene: get_static_scene_objects_4D(display),
-            floor: get_floor_4D(display)
+            scene_bvh: bvh::SceneBvh4D::new(&scene),
+            scene,
+            floor
         }
     }
+
+    pub fn update_scene(&mut self, delta_time: f32) {
+        self.scene.update(delta_time);
+        self.scene_bvh.update(&self.scene);
+    }
+}
+
+//seeded for reproducible scenes, e.g. for visual regression tests
+fn get_scene_rng(options: &SceneOptions) -> SmallRng {
+    match options.seed {
+        Some(seed) => SmallRng::seed_from_u64(seed),
+        None => SmallRng::from_entropy()
+    }
+}
+
+//spawns an entity with the components required for rendering (apart from the material)
+fn spawn_object_3D(scene: &mut Scene3D, transform: Transform3D, mesh: Rc<mesh::StaticUploadedMesh3D>, collider: Collider3D) -> EntityId {
+    let entity = scene.spawn();
+    scene.transforms.insert(entity, transform);
+    scene.meshes.insert(entity, mesh);
+    scene.colliders.insert(entity, collider);
+    entity
+}
+fn spawn_object_4D(scene: &mut Scene4D, transform: Transform4D, mesh: Rc<mesh::StaticUploadedMesh4D>, collider: Collider4D) -> EntityId {
+    let entity = scene.spawn();
+    scene.transforms.insert(entity, transform);
+    scene.meshes.insert(entity, mesh);
+    scene.colliders.insert(entity, collider);
+    entity
 }
 
-fn get_static_scene_objects_3D(display: &glium::Display) -> Vec<RenderableObject3D<materials::SingleColorMaterial>> {
-    let mut objects: Vec<RenderableObject3D<materials::SingleColorMaterial>> = Vec::new();
-    let mut rng = SmallRng::from_entropy();
+fn spawn_static_scene_objects_3D(scene: &mut Scene3D, options: &SceneOptions, display: &GlContext) {
+    let mut rng = get_scene_rng(options);
 
     //big cube
-    objects.push(RenderableObject3D {
-        transform: Transform3D {
+    let cube = Rc::new(mesh::primitives::cube_3D());
+    let big_cube = spawn_object_3D(scene,
+        Transform3D {
             position: Vec3::new(0.0, 1.0, 3.0),
             scale: Vec3::splat(1.5),
             ..Default::default()
-        }.into(),
-        mesh: mesh::primitives::cube_3D().upload_static(display),
-        material: materials::SingleColorMaterial { albedo_color: Vec3::new(1.0, 0.0, 0.0) }
-    });
-
+        },
+        Rc::new(cube.upload_static(display)),
+        Collider3D::Mesh(cube.clone())
+    );
+    scene.materials.insert(big_cube, materials::SingleColorMaterial { albedo_color: Vec3::new(1.0, 0.0, 0.0) }.into());
     //torus
     let torus_major_radius = 0.5;
     let torus_minor_radius = 0.2;
@@ -93,63 +148,69 @@ fn get_static_scene_objects_3D(display: &glium::Display) -> Vec<RenderableObject
         Vec3::ZERO,
         true
     );
-    objects.push(RenderableObject3D {
-        transform: Transform3D {
+    let torus_entity = spawn_object_3D(scene,
+        Transform3D {
             position: Vec3::new(0.0, 3.0, 3.0),
             ..Default::default()
-        }.into(),
-        mesh: torus.upload_static(display),
-        material: materials::SingleColorMaterial { albedo_color: Vec3::new(0.0, 1.0, 0.0) }
-    });
+        },
+        Rc::new(torus.upload_static(display)),
+        Collider3D::Mesh(Rc::new(torus))
+    );
+    scene.materials.insert(torus_entity, materials::SingleColorMaterial { albedo_color: Vec3::new(0.0, 1.0, 0.0) }.into());
 
     const CUBE_COUNT: usize = 5;
     const SPHERE_COUNT: usize = 5;
     const SPAWN_RADIUS: f32 = 7.0;
 
     //random cubes
-    let cube = mesh::primitives::cube_3D();
+    let uploaded_cube = Rc::new(cube.upload_static(display));
     for _ in 0..CUBE_COUNT {
         let position = Vec3::new(rng.gen_range(-1.0..1.0) * SPAWN_RADIUS, 0.3, rng.gen_range(-1.0..1.0) * SPAWN_RADIUS);
-        let orientation = rotation::random_3D_nonuniform(&mut rng);
+        let orientation = if options.uniform_random_rotations {
+            rotation::random_3D_uniform(&mut rng)
+        } else {
+            rotation::random_3D_nonuniform(&mut rng)
+        };
         let color = Vec3::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
 
-        objects.push(RenderableObject3D {
-            transform: Transform3D { position, orientation, ..Default::default() }.into(),
-            mesh: cube.clone().upload_static(display),
-            material: materials::SingleColorMaterial { albedo_color: color }
-        });
+        let entity = spawn_object_3D(scene,
+            Transform3D { position, orientation, ..Default::default() },
+            uploaded_cube.clone(),
+            Collider3D::Mesh(cube.clone())
+        );
+        scene.materials.insert(entity, materials::SingleColorMaterial { albedo_color: color }.into());
     }
 
     //random spheres
-    let sphere = mesh::primitives::sphere_3D(4, 1);
+    let sphere = Rc::new(mesh::primitives::sphere_3D(4, 1).upload_static(display));
     for _ in 0..SPHERE_COUNT {
         let position = Vec3::new(rng.gen_range(-1.0..1.0) * SPAWN_RADIUS, 0.3, rng.gen_range(-1.0..1.0) * SPAWN_RADIUS);
         let color = Vec3::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
 
-        objects.push(RenderableObject3D {
-            transform: Transform3D { position, ..Default::default() }.into(),
-            mesh: sphere.clone().upload_static(display),
-            material: materials::SingleColorMaterial { albedo_color: color }
-        });
+        let entity = spawn_object_3D(scene,
+            Transform3D { position, ..Default::default() },
+            sphere.clone(),
+            Collider3D::Sphere { radius: 1.0 }
+        );
+        scene.materials.insert(entity, materials::SingleColorMaterial { albedo_color: color }.into());
     }
-
-    objects
 }
-fn get_static_scene_objects_4D(display: &glium::Display) -> Vec<RenderableObject4D<materials::SingleColorMaterial>> {
-    let mut objects: Vec<RenderableObject4D<materials::SingleColorMaterial>> = Vec::new();
-    let mut rng = SmallRng::from_entropy();
+fn spawn_static_scene_objects_4D(scene: &mut Scene4D, options: &SceneOptions, display: &GlContext) {
+    let mut rng = get_scene_rng(options);
 
     //big tesseract
-    objects.push(RenderableObject4D {
-        transform: Transform4D {
+    let tesseract = mesh::primitives::tesseract_4D();
+    let tesseract_collider = Collider4D::from_mesh(tesseract.clone());
+    let big_tesseract = spawn_object_4D(scene,
+        Transform4D {
             position: Vec4::new(0.0, 0.0, 1.0, 3.0),
             scale: Vec4::splat(1.5),
             ..Default::default()
-        }.into(),
-        mesh: mesh::primitives::tesseract_4D().upload_static(display),
-        material: materials::SingleColorMaterial { albedo_color: Vec3::new(1.0, 0.0, 0.0) }
-    });
-
+        },
+        Rc::new(tesseract.upload_static(display)),
+        tesseract_collider.clone()
+    );
+    scene.materials.insert(big_tesseract, materials::SingleColorMaterial { albedo_color: Vec3::new(1.0, 0.0, 0.0) }.into());
     //torus
     let torus_major_radius = 0.5;
     let torus_minor_radius = 0.2;
@@ -175,14 +236,15 @@ fn get_static_scene_objects_4D(display: &glium::Display) -> Vec<RenderableObject
         Vec4::ZERO,
         true
     );
-    objects.push(RenderableObject4D {
-        transform: Transform4D {
+    let torus_entity = spawn_object_4D(scene,
+        Transform4D {
             position: Vec4::new(0.0, -3.0, 3.0, 3.0),
             ..Default::default()
-        }.into(),
-        mesh: torus.upload_static(display),
-        material: materials::SingleColorMaterial { albedo_color: Vec3::new(0.0, 0.0, 1.0) }
-    });
+        },
+        Rc::new(torus.upload_static(display)),
+        Collider4D::from_mesh(torus)
+    );
+    scene.materials.insert(torus_entity, materials::SingleColorMaterial { albedo_color: Vec3::new(0.0, 0.0, 1.0) }.into());
 
     //wavy torus
     let wavy_torus = mesh::isosurface::get_connected_isosurface_4D(
@@ -202,76 +264,234 @@ fn get_static_scene_objects_4D(display: &glium::Display) -> Vec<RenderableObject
         Vec4::X * 10.0,
         true
     );
-    objects.push(RenderableObject4D {
-        transform: Transform4D {
+    let wavy_torus_entity = spawn_object_4D(scene,
+        Transform4D {
             position: Vec4::new(0.0, 3.0, 3.0, 3.0),
             ..Default::default()
-        }.into(),
-        mesh: wavy_torus.upload_static(display),
-        material: materials::SingleColorMaterial { albedo_color: Vec3::new(0.0, 1.0, 0.0) }
-    });
+        },
+        Rc::new(wavy_torus.upload_static(display)),
+        Collider4D::from_mesh(wavy_torus)
+    );
+    scene.materials.insert(wavy_torus_entity, materials::SingleColorMaterial { albedo_color: Vec3::new(0.0, 1.0, 0.0) }.into());
 
     const TESSERACT_COUNT: usize = 20;
     const SPHERE_COUNT: usize = 20;
     const SPAWN_RADIUS: f32 = 7.0;
 
     //random tesseracts
-    let tesseract = mesh::primitives::tesseract_4D();
+    let uploaded_tesseract = Rc::new(tesseract.upload_static(display));
     for _ in 0..TESSERACT_COUNT {
         let position = Vec4::new(rng.gen_range(-1.0..1.0) * SPAWN_RADIUS, rng.gen_range(-1.0..1.0) * SPAWN_RADIUS, 0.3, rng.gen_range(-1.0..1.0) * SPAWN_RADIUS);
-        let orientation = rotation::random_4D_nonuniform(&mut rng);
+        let orientation = if options.uniform_random_rotations {
+            rotation::random_4D_uniform(&mut rng)
+        } else {
+            rotation::random_4D_nonuniform(&mut rng)
+        };
         let color = Vec3::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
 
-        objects.push(RenderableObject4D {
-            transform: Transform4D { position, orientation, ..Default::default() }.into(),
-            mesh: tesseract.clone().upload_static(display),
-            material: materials::SingleColorMaterial { albedo_color: color }
-        });
+        let entity = spawn_object_4D(scene,
+            Transform4D { position, orientation, ..Default::default() },
+            uploaded_tesseract.clone(),
+            tesseract_collider.clone()
+        );
+        scene.materials.insert(entity, materials::SingleColorMaterial { albedo_color: color }.into());
     }
 
     //random spheres
-    let sphere = mesh::primitives::sphere_4D(4, 1);
+    let sphere = Rc::new(mesh::primitives::sphere_4D(4, 1).upload_static(display));
     for _ in 0..SPHERE_COUNT {
         let position = Vec4::new(rng.gen_range(-1.0..1.0) * SPAWN_RADIUS, rng.gen_range(-1.0..1.0) * SPAWN_RADIUS, 0.3, rng.gen_range(-1.0..1.0) * SPAWN_RADIUS);
         let color = Vec3::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
 
-        objects.push(RenderableObject4D {
-            transform: Transform4D { position, ..Default::default() }.into(),
-            mesh: sphere.clone().upload_static(display),
-            material: materials::SingleColorMaterial { albedo_color: color }
-        });
+        let entity = spawn_object_4D(scene,
+            Transform4D { position, ..Default::default() },
+            sphere.clone(),
+            Collider4D::Hypersphere { radius: 1.0 }
+        );
+        scene.materials.insert(entity, materials::SingleColorMaterial { albedo_color: color }.into());
     }
+}
+
+//tesseracts passing through the slice
+fn spawn_animated_objects_4D(scene: &mut Scene4D, display: &GlContext) {
+    let tesseract = mesh::primitives::tesseract_4D();
+    let uploaded_tesseract = Rc::new(tesseract.upload_static(display));
+    let tesseract_collider = Collider4D::from_mesh(tesseract);
 
-    objects
+    //spinning in place. The planes commute, so the spin is uniform
+    let spinning_transform = Transform4D {
+        position: Vec4::new(0.0, 1.5, 1.0, 6.0),
+        ..Default::default()
+    };
+    let spinning = spawn_object_4D(scene, spinning_transform, uploaded_tesseract.clone(), tesseract_collider.clone());
+    scene.materials.insert(spinning, materials::SingleColorMaterial { albedo_color: Vec3::new(1.0, 0.5, 0.0) }.into());
+    scene.animations.insert(spinning, Animation4D::spinning(spinning_transform, vec![
+        AngularVelocity4D { plane: RotationPlane4D::ZW, radians_per_second: 0.5 },
+        AngularVelocity4D { plane: RotationPlane4D::XY, radians_per_second: 0.3 }
+    ]));
+
+    //sliding back and forth through the slice, while tumbling
+    let keyframe = |time: f32, x: f32, angle: f32| Keyframe4D {
+        time,
+        position: Vec4::new(x, -1.5, 1.0, 6.0),
+        orientation: get_rotor_from_angles([0.0, 0.0, 0.0, angle, 0.0, 0.5 * angle]),
+        scale: Vec4::ONE
+    };
+    let sliding = spawn_object_4D(scene, Transform4D::IDENTITY, uploaded_tesseract, tesseract_collider);
+    scene.materials.insert(sliding, materials::SingleColorMaterial { albedo_color: Vec3::new(0.5, 0.0, 1.0) }.into());
+    scene.animations.insert(sliding, Animation4D::keyframed(
+        vec![
+            keyframe(0.0, -1.5, 0.0),
+            keyframe(4.0, 1.5, TAU / 4.0)
+        ],
+        Interpolation::Smooth,
+        PlaybackMode::PingPong
+    ));
+}
+
+/* A chessboard patterned sphere and a shiny one. They have their own RNG, so that the rest of the scene
+   stays the same for a given seed */
+fn spawn_material_showcase_3D(scene: &mut Scene3D, options: &SceneOptions, display: &GlContext) {
+    let mut rng = get_scene_rng(options);
+    let color = Vec3::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+
+    let sphere = Rc::new(mesh::primitives::sphere_3D(4, 1).upload_static(display));
+    let chessboard_sphere = spawn_object_3D(scene,
+        Transform3D {
+            position: Vec3::new(-3.0, 1.0, 3.0),
+            ..Default::default()
+        },
+        sphere.clone(),
+        Collider3D::Sphere { radius: 1.0 }
+    );
+    scene.materials.insert(chessboard_sphere, materials::ChessboardMaterial {
+        color_A: color,
+        color_B: 0.5 * color,
+        square_width: 0.25
+    }.into());
+    let shiny_sphere = spawn_object_3D(scene,
+        Transform3D {
+            position: Vec3::new(-3.0, 1.0, 0.5),
+            ..Default::default()
+        },
+        sphere,
+        Collider3D::Sphere { radius: 1.0 }
+    );
+    scene.materials.insert(shiny_sphere, materials::PbrMaterial {
+        albedo_color: color,
+        roughness: rng.gen_range(0.2..0.8),
+        metalness: 1.0
+    }.into());
+}
+fn spawn_material_showcase_4D(scene: &mut Scene4D, options: &SceneOptions, display: &GlContext) {
+    let mut rng = get_scene_rng(options);
+    let color = Vec3::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+
+    let sphere = Rc::new(mesh::primitives::sphere_4D(4, 1).upload_static(display));
+    let chessboard_sphere = spawn_object_4D(scene,
+        Transform4D {
+            position: Vec4::new(0.0, -3.0, 1.0, 0.0),
+            ..Default::default()
+        },
+        sphere.clone(),
+        Collider4D::Hypersphere { radius: 1.0 }
+    );
+    scene.materials.insert(chessboard_sphere, materials::ChessboardMaterial {
+        color_A: color,
+        color_B: 0.5 * color,
+        square_width: 0.25
+    }.into());
+    let shiny_sphere = spawn_object_4D(scene,
+        Transform4D {
+            position: Vec4::new(0.0, -3.0, 1.0, -2.5),
+            ..Default::default()
+        },
+        sphere,
+        Collider4D::Hypersphere { radius: 1.0 }
+    );
+    scene.materials.insert(shiny_sphere, materials::PbrMaterial {
+        albedo_color: color,
+        roughness: rng.gen_range(0.2..0.8),
+        metalness: 1.0
+    }.into());
 }
 
-fn get_floor_3D(display: &glium::Display) -> RenderableObject3D<materials::ChessboardMaterial> {
-    RenderableObject3D {
-        transform: Transform3D {
+fn spawn_floor_3D(scene: &mut Scene3D, display: &GlContext) {
+    let quad = mesh::primitives::quad_3D();
+    let floor = spawn_object_3D(scene,
+        Transform3D {
             scale: Vec3::splat(100.0),
             orientation: switch_matrix3_columns(Mat3::IDENTITY, 1, 2),
             ..Default::default()
-        }.into(),
-        mesh: mesh::primitives::quad_3D().upload_static(display),
-        material: materials::ChessboardMaterial {
-            color_A: Vec3::new(1.0, 1.0, 1.0),
-            color_B: Vec3::new(0.8, 0.8, 0.8),
-            square_width: 0.5
-        }
-    }
+        },
+        Rc::new(quad.upload_static(display)),
+        Collider3D::Mesh(Rc::new(quad))
+    );
+    scene.materials.insert(floor, materials::ChessboardMaterial {
+        color_A: Vec3::new(1.0, 1.0, 1.0),
+        color_B: Vec3::new(0.8, 0.8, 0.8),
+        square_width: 0.5
+    }.into());
 }
-fn get_floor_4D(display: &glium::Display) -> RenderableObject4D<materials::ChessboardMaterial> {
-    RenderableObject4D {
-        transform: Transform4D {
+fn spawn_floor_4D(scene: &mut Scene4D, display: &GlContext) -> EntityId {
+    let cube = mesh::primitives::cube_4D();
+    let floor = spawn_object_4D(scene,
+        Transform4D {
             scale: Vec4::splat(100.0),
             orientation: switch_matrix4_columns(Mat4::IDENTITY, 2, 3),
             ..Default::default()
-        }.into(),
-        mesh: mesh::primitives::cube_4D().upload_static(display),
-        material: materials::ChessboardMaterial {
-            color_A: Vec3::new(1.0, 1.0, 1.0),
-            color_B: Vec3::new(0.8, 0.8, 0.8),
-            square_width: 0.5
-        }
+        },
+        Rc::new(cube.upload_static(display)),
+        Collider4D::from_mesh(cube)
+    );
+    scene.materials.insert(floor, materials::ChessboardMaterial {
+        color_A: Vec3::new(1.0, 1.0, 1.0),
+        color_B: Vec3::new(0.8, 0.8, 0.8),
+        square_width: 0.5
+    }.into());
+    floor
+}
+
+//a dim sun and a few colored lamps
+fn spawn_lights_3D(scene: &mut Scene3D) {
+    let sun = scene.spawn();
+    scene.lights.insert(sun, Light3D::Directional {
+        direction: Vec3::new(0.3, -1.0, 0.5),
+        color: Vec3::splat(0.6),
+        casts_shadows: true
+    });
+
+    for (position, color) in [
+        (Vec3::new(-4.0, 2.0, 4.0), Vec3::new(1.0, 0.6, 0.3)),
+        (Vec3::new(4.0, 2.0, -4.0), Vec3::new(0.3, 0.6, 1.0))
+    ] {
+        let lamp = scene.spawn();
+        scene.transforms.insert(lamp, Transform3D { position, ..Default::default() });
+        scene.lights.insert(lamp, Light3D::Point {
+            color,
+            linear_attenuation: 0.14,
+            quadratic_attenuation: 0.07
+        });
     }
-}
\ No newline at end of file
+}
+fn spawn_lights_4D(scene: &mut Scene4D) {
+    let sun = scene.spawn();
+    scene.lights.insert(sun, Light4D::Directional {
+        direction: Vec4::new(0.3, 0.5, -1.0, 0.2),
+        color: Vec3::splat(0.6),
+        casts_shadows: true
+    });
+
+    for (position, color) in [
+        (Vec4::new(-4.0, -4.0, 2.0, 4.0), Vec3::new(1.0, 0.6, 0.3)),
+        (Vec4::new(4.0, 4.0, 2.0, -4.0), Vec3::new(0.3, 0.6, 1.0))
+    ] {
+        let lamp = scene.spawn();
+        scene.transforms.insert(lamp, Transform4D { position, ..Default::default() });
+        scene.lights.insert(lamp, Light4D::Point {
+            color,
+            linear_attenuation: 0.14,
+            quadratic_attenuation: 0.07
+        });
+    }
+}
@@ -1,15 +1,30 @@
 use crate::options::Options;
 use glam::UVec2;
 use crate::clock::AverageFrameTimings;
+use crate::game::bookmarks::BookmarkAction;
+use crate::game::camera_path::{CameraPathAction, DEFAULT_CAMERA_PATH_FILE};
+use crate::game::inspection::InspectionAction;
+use crate::events::input_recording::RecordedFiles;
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
 
 pub struct GlobalData {
     pub close_requested: bool,
+    pub screenshot_requested: bool,
+    pub bookmark_action: Option<BookmarkAction>,//handled on the next update
+    pub camera_path_action: Option<CameraPathAction>,//handled on the next update
+    pub camera_path_file: PathBuf,
+    pub replayed_files: Option<RecordedFiles>,//used instead of the bookmark and camera path files while replaying recorded input
+    pub inspection_action: Option<InspectionAction>,//handled on the next update
+    pub recording_toggle_requested: bool,
+    pub fixed_delta_time: Option<f32>,//replaces wall-clock time in the simulation while recording
     pub resolution: UVec2,
     pub frame_timings: AverageFrameTimings,
     pub mouse_grabbed: bool,
     pub info_screen_visible: bool,
     pub visual_mode: VisualMode,
     pub polygon_mode: glium::draw_parameters::PolygonMode,//Fill, unless debugging
+    pub slice_offset: f32,//moves the slicing hyperplane along the camera's x axis, in Degenerate3D and the 4D slicing modes
     pub options: Options
 }
 impl GlobalData {
@@ -17,12 +32,21 @@ impl GlobalData {
         let options = Options::load();
         GlobalData {
             close_requested: false,
+            screenshot_requested: false,
+            bookmark_action: None,
+            camera_path_action: None,
+            camera_path_file: PathBuf::from(DEFAULT_CAMERA_PATH_FILE),
+            replayed_files: None,
+            inspection_action: None,
+            recording_toggle_requested: false,
+            fixed_delta_time: None,
             resolution: UVec2::from_array(options.user.graphics.default_resolution),
             frame_timings: AverageFrameTimings::new_nan(),
             mouse_grabbed: false,
             info_screen_visible: false,
             visual_mode: VisualMode::from_int(options.user.default_mode),
             polygon_mode: glium::draw_parameters::PolygonMode::Fill,
+            slice_offset: 0.0,
             options: options
         }
     }
@@ -36,16 +60,19 @@ impl GlobalData {
     }
 
     pub fn is_4D_active(&self) -> bool {
-        self.visual_mode == VisualMode::Degenerate4D
+        matches!(self.visual_mode, VisualMode::Degenerate4D | VisualMode::ThickSlice4D | VisualMode::Projection4D | VisualMode::Combined4D)
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VisualMode {
     Normal3D,
     Degenerate3D,
     Combined3D,
-    Degenerate4D
+    Degenerate4D,
+    ThickSlice4D,
+    Projection4D,
+    Combined4D
 }
 impl VisualMode {
     pub fn from_int(int: u32) -> Self {
@@ -54,6 +81,9 @@ impl VisualMode {
             2 => Self::Combined3D,
             3 => Self::Degenerate3D,
             4 => Self::Degenerate4D,
+            5 => Self::ThickSlice4D,
+            6 => Self::Projection4D,
+            7 => Self::Combined4D,
             _ => panic!("Unknown visual mode {int}")
         }
     }
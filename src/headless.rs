@@ -0,0 +1,99 @@
+use crate::arguments::HeadlessArguments;
+use crate::global_data::GlobalData;
+use crate::game::world::Multiverse;
+use crate::game::bookmarks::go_to_bookmark;
+use crate::game::camera_path::CameraPathPlayback;
+use crate::renderer::Renderer;
+use glium::glutin;
+use glium::backend::Facade;
+use std::path::{Path, PathBuf};
+
+//winit panics when there's no display server, like on CI without xvfb
+fn create_event_loop() -> glutin::event_loop::EventLoop<()> {
+    let default_panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| ()));
+    let event_loop = std::panic::catch_unwind(glutin::event_loop::EventLoop::new);
+    std::panic::set_hook(default_panic_hook);
+    event_loop.unwrap_or_else(|_| {
+        eprintln!("Headless mode needs a display server, e.g. run under xvfb-run");
+        std::process::exit(2);
+    })
+}
+
+fn exit_without_gl(error: &dyn std::fmt::Display) -> ! {
+    eprintln!("Could not create an OpenGL context: {error}\nHeadless mode needs a display server with OpenGL, e.g. run under xvfb-run");
+    std::process::exit(2);
+}
+
+//renders without a window and writes each frame to a PNG, for documentation images and visual regression tests
+pub fn run(arguments: &HeadlessArguments, bookmark: Option<&str>, follow_camera_path: bool, mut global_data: GlobalData) {
+    //glutin's headless contexts still connect to a display server, but don't need a window on it
+    let event_loop = create_event_loop();
+    let context = glutin::ContextBuilder::new()
+        .with_depth_buffer(24)
+        .build_headless(&event_loop, glutin::dpi::PhysicalSize::new(global_data.resolution.x, global_data.resolution.y))
+        .unwrap_or_else(|error| exit_without_gl(&error));
+    let headless_renderer = glium::HeadlessRenderer::new(context).unwrap_or_else(|error| exit_without_gl(&error));
+    let display = headless_renderer.get_context();
+
+    let mut renderer = Renderer::new(display, &global_data);
+    let mut multiverse = Multiverse::new(&global_data, display);
+    if let Some(bookmark) = bookmark {
+        if let Err(message) = go_to_bookmark(bookmark, &mut multiverse, &mut global_data) {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    }
+    //loaded before placing the camera, which would move the eyes of the bookmarked players in it
+    let mut camera_path = None;
+    if follow_camera_path {
+        match CameraPathPlayback::load(&global_data.camera_path_file, &multiverse.world_4D.player) {
+            Ok(playback) => camera_path = Some(playback),
+            Err(message) => {
+                eprintln!("{message}");
+                std::process::exit(2);
+            }
+        }
+    }
+    if let Some(camera_transform) = arguments.camera_transform {
+        multiverse.world_4D.player.place_camera(camera_transform);
+    }
+
+    for frame_index in 0..arguments.frame_count {
+        if frame_index > 0 {
+            advance_scene(&mut multiverse, arguments.frame_time, &global_data);
+        }
+        if let Some(playback) = &mut camera_path {
+            if frame_index > 0 {
+                playback.advance(arguments.frame_time);
+            }
+            playback.apply(&mut multiverse.world_4D.player);
+        }
+
+        let image = renderer.render_frame_offscreen(display, &multiverse, &global_data);
+        let path = get_frame_path(&arguments.output_path, frame_index, arguments.frame_count);
+        image.save_png(&path).unwrap_or_else(|error| panic!("Could not write {}: {error}", path.display()));
+        println!("Wrote {}", path.display());
+    }
+}
+
+//like game::update_game, but with a fixed time step and without a player to control
+fn advance_scene(multiverse: &mut Multiverse, delta_time: f32, global_data: &GlobalData) {
+    if global_data.is_4D_active() {
+        multiverse.world_4D.update_scene(delta_time);
+    }
+    else {
+        multiverse.world_3D.scene.update(delta_time);
+    }
+}
+
+//frames are numbered like image_0003.png, unless there's only one
+fn get_frame_path(output_path: &Path, frame_index: u32, frame_count: u32) -> PathBuf {
+    if frame_count == 1 {
+        return output_path.to_path_buf();
+    }
+
+    let stem = output_path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = output_path.extension().unwrap_or("png".as_ref()).to_string_lossy();
+    output_path.with_file_name(format!("{stem}_{frame_index:04}.{extension}"))
+}
@@ -1,15 +1,13 @@
 use crate::options::AsVector;
-use crate::renderer::text_rendering; 
+use crate::renderer::{text_rendering, GlContext};
 use crate::global_data::GlobalData;
 use crate::game::world::Multiverse;
-use glium_glyph::glyph_brush;
+use crate::game::picking;
 use std::fmt::Display;
-use glam::Vec2;
-use std::f32::consts::TAU;
 
-pub fn render_info_screen(
-    target: &mut glium::Frame,
-    display: &glium::Display,
+pub fn render_info_screen<S: glium::Surface>(
+    target: &mut S,
+    display: &GlContext,
     text_renderer: &mut text_rendering::TextRenderer,
     multiverse: &Multiverse,
     global_data: &GlobalData)
@@ -19,16 +17,34 @@ pub fn render_info_screen(
     let uncapped_FPS = global_data.frame_timings.uncapped_fps;
     let uncapped_ms_per_frame = global_data.frame_timings.uncapped_milliseconds_per_frame;
     let visual_mode = global_data.visual_mode.to_string();
+    let slice_offset = global_data.slice_offset;
     let camera_position_3D = CustomFormatted(multiverse.world_3D.player.get_camera_world_position());
     let camera_position_4D = CustomFormatted(multiverse.world_4D.player.get_camera_world_position());
     let look_direction_3D = CustomFormatted(multiverse.world_3D.player.get_pretty_camera_orientation());
     let look_direction_4D = CustomFormatted(multiverse.world_4D.player.get_pretty_camera_orientation());
+    let camera_scheme_4D = multiverse.world_4D.player.camera_scheme;
+    let camera_path = match &multiverse.camera_path {
+        Some(playback) => format!("Camera path: {:.2} / {:.2} s{}\n",
+            playback.time(), playback.duration(), if playback.is_paused { ", paused" } else { "" }),
+        None => String::new()
+    };
+    let crosshair_target = match global_data.is_4D_active() {
+        true => picking::ray_cast_4D(&multiverse.world_4D, &picking::get_camera_ray_4D(&multiverse.world_4D.player, global_data))
+            .map(|hit| format_crosshair_target(hit.entity.0, hit.distance, CustomFormatted(hit.point), CustomFormatted(hit.normal))),
+        false => picking::ray_cast_3D(&multiverse.world_3D.scene, &picking::get_camera_ray_3D(&multiverse.world_3D.player, global_data))
+            .map(|hit| format_crosshair_target(hit.entity.0, hit.distance, CustomFormatted(hit.point), CustomFormatted(hit.normal)))
+    }.unwrap_or_else(|| "Crosshair: nothing\n".to_string());
+    let inspection = match &multiverse.inspection {
+        Some(inspection) => format!("Inspecting: entity {}, distance {:.2}\n", inspection.entity.0, inspection.distance),
+        None => String::new()
+    };
 
     let text = format!("\
 Resolution: {resolution}
 FPS: {capped_FPS:.1}, uncapped 1 / {uncapped_ms_per_frame:.2} ms = {uncapped_FPS:.1}
 Mode: {visual_mode}
-
+Slice offset: {slice_offset:.2}
+{crosshair_target}
 3D:
 Position: {camera_position_3D:.2}
 Look direction: {look_direction_3D:.2}
@@ -36,38 +52,16 @@ Look direction: {look_direction_3D:.2}
 4D:
 Position: {camera_position_4D:.2}
 Look direction: {look_direction_4D:.2}
-");
+Controls: {camera_scheme_4D:?}
+{camera_path}{inspection}");
 
-    let font_size = global_data.options.user.info_screen.font_size;
     let screen_position = global_data.options.user.info_screen.position.as_vector();
+    text_renderer.queue_outlined_text(&text, screen_position, global_data);
+    text_renderer.draw_queued(display, target);
+}
 
-    let formatted_text = glyph_brush::Text {
-        text: &text,
-        scale: font_size.into(),
-        font_id: text_renderer.fonts.info_screen,
-        ..Default::default()
-    };
-    let mut section = glyph_brush::Section {
-        text: vec![formatted_text],
-        ..Default::default()
-    };
-
-    //outline
-    section.text[0].extra.color = [0.0, 0.0, 0.0, 1.0];
-    let outline_size = global_data.options.user.info_screen.relative_outline_size * font_size;
-    let offsets = get_points_on_unit_circle(global_data.options.user.info_screen.outline_quality);
-    for offset in offsets {
-        section.screen_position = (screen_position + outline_size * offset).into();
-
-        text_renderer.brush.queue(section.clone());
-    }
-    
-    //main text
-    section.text[0].extra.color = [1.0, 1.0, 1.0, 1.0];
-    section.screen_position = screen_position.into();
-    text_renderer.brush.queue(section);
-
-    text_renderer.brush.draw_queued(display, target);
+fn format_crosshair_target<V: Display>(entity: usize, distance: f32, point: V, normal: V) -> String {
+    format!("Crosshair: entity {entity} at {distance:.2}\nHit point: {point:.2}\nHit normal: {normal:.2}\n")
 }
 
 struct CustomFormatted<V>(V);
@@ -113,17 +107,3 @@ impl Display for CustomFormatted<glam::Vec4> {
         )
     }
 }
-
-fn get_points_on_unit_circle(count: usize) -> Vec<Vec2> {
-    let rotation = Vec2::from_angle(TAU / count as f32);
-    let mut points = Vec::<Vec2>::with_capacity(count);
-
-    for _ in 0..count {
-        points.push(match points.last() {
-            Some(v) => v.rotate(rotation),
-            None => Vec2::X
-        });
-    }
-
-    points
-}
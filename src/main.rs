@@ -9,21 +9,76 @@ pub mod info_screen;
 pub mod errors;
 pub mod clock;
 pub mod combinations;
+pub mod arguments;
+pub mod headless;
+pub mod screenshot;
+pub mod recording;
 
 use glium::glutin;
+use glium::backend::Facade;
+use events::input_recording::{InputRecorder, InputReplay};
 
 fn main() {
     assert_request_for_best_gpu_made_windows();
 
+    if std::env::args().any(|argument| argument == "--help") {
+        println!("{}", arguments::USAGE);
+        return;
+    }
+    let arguments = match arguments::Arguments::parse(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{message}\n\n{}", arguments::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     let mut global_data = global_data::GlobalData::new();
+    arguments.apply(&mut global_data);
+    if let Some(headless_arguments) = &arguments.headless {
+        headless::run(headless_arguments, arguments.bookmark.as_deref(), arguments.camera_path.is_some(), global_data);
+        return;
+    }
 
     let glutin_event_loop = glutin::event_loop::EventLoop::new();
     let display = get_display(&glutin_event_loop, &global_data);
     
+    let mut input_replay = arguments.replay_input.as_ref().map(|path| match InputReplay::load(path) {
+        Ok(replay) => replay,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    });
+    if let Some(replay) = &input_replay {
+        replay.apply_options(&mut global_data);
+    }
+    if arguments.record_input.is_some() {
+        events::input_recording::choose_seed(&mut global_data);
+    }
+
     let mut input_handler = events::input::InputHandler::new();
-    let mut renderer = renderer::Renderer::new(&display, &global_data);
-    let mut multiverse = game::world::Multiverse::new(&global_data, &display);
+    let mut gamepad_input = events::gamepad::GamepadInput::new();
+    let mut renderer = renderer::Renderer::new(display.get_context(), &global_data);
+    let mut multiverse = game::world::Multiverse::new(&global_data, display.get_context());
+    if let Some(bookmark) = &arguments.bookmark {
+        if let Err(message) = game::bookmarks::go_to_bookmark(bookmark, &mut multiverse, &mut global_data) {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    }
+    if let Some(replay) = &input_replay {
+        replay.place_players(&mut multiverse);
+    }
+    let mut input_recorder = arguments.record_input.as_ref().map(|path| match InputRecorder::new(path, &multiverse, &global_data) {
+        Ok(recorder) => recorder,
+        Err(error) => {
+            eprintln!("Could not record input to {}: {error}", path.display());
+            std::process::exit(2);
+        }
+    });
     let mut clock = clock::MainLoopClock::new();
+    let mut recorder = recording::Recorder::default();
 
     events::set_mouse_grab(true, &mut global_data, &display);
 
@@ -31,8 +86,36 @@ fn main() {
         match event {
             glutin::event::Event::MainEventsCleared =>
             {
-                game::update_game(&mut multiverse, &input_handler, &mut global_data);
+                match input_replay {
+                    Some(_) => gamepad_input.discard_events(),
+                    None => events::handle_gamepad_input(&mut gamepad_input, &mut input_handler, &global_data)
+                }
+                let mut delta_time = game::get_delta_time(&mut multiverse, &global_data);
+                if let Some(replay) = &mut input_replay {
+                    match replay.next_frame(&mut input_handler, &mut global_data) {
+                        Some(recorded_delta_time) => delta_time = recorded_delta_time,
+                        None => {
+                            println!("Input replay finished");
+                            input_handler = events::input::InputHandler::new();
+                            input_replay = None;
+                            global_data.replayed_files = None;
+                        }
+                    }
+                }
+                events::handle_pressed_actions(&input_handler, &mut global_data, &display);
+                if let Some(recorder) = &mut input_recorder {
+                    if let Err(error) = recorder.record_frame(&input_handler, delta_time, &global_data) {
+                        eprintln!("Input recording stopped: {error}");
+                        input_recorder = None;
+                    }
+                }
+                game::update_game(&mut multiverse, &input_handler, &mut global_data, delta_time);
                 renderer.render_frame(&display, &multiverse, &mut global_data);
+                if global_data.screenshot_requested {
+                    screenshot::save_screenshot(&display, &multiverse, &global_data);
+                    global_data.screenshot_requested = false;
+                }
+                recorder.update(&display, &mut global_data);
                 input_handler.reset_deltas();
 
                 let is_end_of_measurement_interval = clock.tick(global_data.options.user.graphics.max_fps);
@@ -41,8 +124,9 @@ fn main() {
                     display.gl_window().window().set_title(&format!("4D game | {:.2} ms/f", global_data.frame_timings.uncapped_milliseconds_per_frame));
                 }
             },
-            other => {
-                events::handle_event(other, &mut input_handler, &mut global_data, &display);
+            other => match input_replay {
+                Some(_) => events::handle_event_while_replaying(other, &mut global_data),
+                None => events::handle_event(other, &mut input_handler, &mut global_data)
             }
         }
 
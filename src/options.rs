@@ -1,8 +1,10 @@
 use glam::{Vec3, Vec2};
 use serde::{Deserialize, de::DeserializeOwned, Serialize};
 use std::fs;
+use crate::events::actions::KeyBindings;
+use crate::game::player::CameraScheme4D;
 
-#[derive(Debug)]
+#[derive(Serialize, Debug)]
 pub struct Options {
     pub user: UserOptions,
     pub dev: DevOptions
@@ -12,7 +14,10 @@ pub struct Options {
 pub struct UserOptions {
     pub graphics: UserGraphicsOptions,
     pub input: InputOptions,
+    pub key_bindings: KeyBindings,
     pub info_screen: InfoScreenOptions,
+    pub screenshots: ScreenshotOptions,
+    pub recording: RecordingOptions,
     pub default_mode: u32
 }
 
@@ -21,6 +26,9 @@ pub struct DevOptions {
     pub camera: CameraOptions,
     pub player: PlayerOptions,
     pub light: LightOptions,
+    pub shadow: ShadowOptions,
+    pub fog: FogOptions,
+    pub scene: SceneOptions,
     pub debug: DebugOptions
 }
 
@@ -29,7 +37,18 @@ pub struct UserGraphicsOptions {
     pub default_resolution: [u32; 2],
     pub max_fps: f32,
     pub combined_render_degenerate_strength: f32,
-    pub skeleton_width: f32
+    pub skeleton_width: f32,
+    //ThickSlice4D renders thick_slice_layer_count slices on each side of the main one, up to thick_slice_half_thickness away
+    pub thick_slice_half_thickness: f32,
+    pub thick_slice_layer_count: u32,
+    pub thick_slice_opacity: f32,//of the closest layers, the others fade out with distance
+    //Projection4D shows the volume the 4D camera projects onto from outside, orbiting its center (in degrees)
+    pub projection_4D_opacity: f32,
+    pub projection_4D_view_yaw: f32,
+    pub projection_4D_view_pitch: f32,
+    pub projection_4D_view_distance: f32,
+    pub combined_4D_slice_offset: f32,//distance of the side slices of Combined4D from the main one
+    pub crosshair_size: u32//in pixels, 0 hides it
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -40,24 +59,69 @@ pub struct CameraOptions {
     pub far_plane: f32
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InputOptions {
-    pub mouse_sensitivity: f32
+    pub mouse_sensitivity: f32,
+    pub slice_offset_speed: f32,//per second, while a key is held
+    pub slice_offset_scroll_step: f32,//per scrolled line
+    pub camera_path_scrub_speed: f32,//seconds of the path per second, while a key is held
+    //sticks and triggers are ignored below the deadzone. The left stick and the triggers move, and the right stick looks around like the mouse
+    pub gamepad_deadzone: f32,
+    pub gamepad_move_sensitivity: f32,//relative to keys
+    pub gamepad_look_sensitivity: f32,//radians per second, with the stick all the way
+    pub camera_scheme_4D: CameraScheme4D,//switched with cycle_camera_scheme or by recalling a bookmark, until the options are reloaded
+    pub orbit_distance: f32,
+    pub inspect_zoom_step: f32//relative change of the inspection distance per scrolled line
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PlayerOptions {
     pub walking_speed: f32
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LightOptions {
-    pub light_color: [f32; 3],
     pub ambient_color: [f32; 3],
+    pub max_point_lights: usize,//the closest ones to the camera are used
+    pub max_directional_lights: usize,
+    //a point light that follows the camera
+    pub camera_light: bool,
+    pub light_color: [f32; 3],
     pub linear_attenuation: f32,
     pub quadratic_attenuation: f32
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShadowOptions {
+    pub enabled: bool,
+    pub resolution: u32,
+    pub layer_count_4D: u32,//resolution of the 4D shadow map along the third dimension
+    pub extent: f32,//half of the size of the shadowed area around the camera
+    pub depth_range: f32,//how far from the camera (along the light direction) objects still cast shadows
+    pub bias: f32
+}
+
+//depth cues of the 4D view
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FogOptions {
+    pub enabled: bool,
+    pub color: [f32; 3],
+    pub start: f32,//distance along the view direction where the fog begins
+    pub end: f32,//distance along the view direction where only the fog is visible
+    //tints fragments by their signed distance from the slice hyperplane
+    pub slice_tint: bool,
+    pub slice_tint_positive_color: [f32; 3],
+    pub slice_tint_negative_color: [f32; 3],
+    pub slice_tint_distance: f32,//distance from the slice where the tint is the strongest
+    pub slice_tint_strength: f32
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SceneOptions {
+    pub uniform_random_rotations: bool,
+    pub seed: Option<u64>//random every time if null
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct InfoScreenOptions {
     pub font_name: String,
@@ -67,6 +131,26 @@ pub struct InfoScreenOptions {
     pub outline_quality: usize
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScreenshotOptions {
+    pub directory: String,
+    pub write_sidecar: bool//a JSON file next to the image, with everything needed to reproduce the view
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordingOptions {
+    pub directory: String,
+    pub frame_rate: f32,//of the video, the simulation advances by 1 / frame_rate each frame regardless of how long rendering takes
+    pub format: RecordingFormat
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    Png,//numbered images
+    Raw//a single file of packed RGB frames, for piping to a video encoder
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DebugOptions {
     pub line_width: f32,
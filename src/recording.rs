@@ -0,0 +1,115 @@
+use crate::global_data::GlobalData;
+use crate::options::{RecordingFormat, RecordingOptions};
+use crate::renderer::image_file::Image;
+use crate::clock::get_timestamp;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+const RAW_STREAM_FILE_NAME: &str = "frames.rgb";
+
+//dumps every frame to disk while the simulation advances at a fixed time step, so videos don't jitter no matter how slowly the frames render
+#[derive(Default)]
+pub struct Recorder {
+    recording: Option<Recording>
+}
+struct Recording {
+    directory: PathBuf,
+    format: RecordingFormat,
+    frame_rate: f32,
+    frame_count: u32,
+    dimensions: Option<(u32, u32)>,//of the first frame, since a raw stream can't change size
+    raw_stream: Option<BufWriter<File>>
+}
+
+impl Recorder {
+    //call after Renderer::render_frame, so that the frame is finished
+    pub fn update(&mut self, display: &glium::Display, global_data: &mut GlobalData) {
+        if let Some(recording) = &mut self.recording {
+            if let Err(error) = recording.record_frame(display) {
+                eprintln!("Recording stopped: {error}");
+                self.stop(global_data);
+            }
+        }
+
+        if global_data.recording_toggle_requested {
+            global_data.recording_toggle_requested = false;
+            match self.recording {
+                Some(_) => self.stop(global_data),
+                None => self.start(global_data)
+            }
+        }
+    }
+
+    fn start(&mut self, global_data: &mut GlobalData) {
+        match Recording::new(&global_data.options.user.recording) {
+            Ok(recording) => {
+                println!("Recording to {}", recording.directory.display());
+                global_data.fixed_delta_time = Some(1.0 / recording.frame_rate);
+                self.recording = Some(recording);
+            },
+            Err(error) => eprintln!("Could not start recording: {error}")
+        }
+    }
+
+    fn stop(&mut self, global_data: &mut GlobalData) {
+        if let Some(recording) = self.recording.take() {
+            recording.finish();
+        }
+        global_data.fixed_delta_time = None;
+    }
+}
+
+impl Recording {
+    fn new(options: &RecordingOptions) -> io::Result<Self> {
+        let directory = PathBuf::from(&options.directory).join(format!("recording_{}", get_timestamp()));
+        fs::create_dir_all(&directory)?;
+
+        let raw_stream = match options.format {
+            RecordingFormat::Png => None,
+            RecordingFormat::Raw => Some(BufWriter::new(File::create(directory.join(RAW_STREAM_FILE_NAME))?))
+        };
+
+        Ok(Self {
+            directory,
+            format: options.format,
+            frame_rate: options.frame_rate,
+            frame_count: 0,
+            dimensions: None,
+            raw_stream
+        })
+    }
+
+    fn record_frame(&mut self, display: &glium::Display) -> io::Result<()> {
+        let image = Image::from_raw(display.read_front_buffer().unwrap());
+        match self.dimensions {
+            None => self.dimensions = Some(image.dimensions),
+            Some(dimensions) if dimensions != image.dimensions => return Err(io::Error::other("the window was resized")),
+            Some(_) => ()
+        }
+
+        match &mut self.raw_stream {
+            None => image.save_png(&self.directory.join(format!("frame_{:05}.png", self.frame_count)))?,
+            Some(raw_stream) => raw_stream.write_all(&image.get_rgb_pixels())?
+        }
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    //prints how to turn the frames into a video
+    fn finish(self) {
+        let Self { directory, format, frame_rate, frame_count, dimensions, raw_stream } = self;
+        if let Some(mut raw_stream) = raw_stream {
+            if let Err(error) = raw_stream.flush() {
+                eprintln!("Could not finish {RAW_STREAM_FILE_NAME}: {error}");
+            }
+        }
+
+        println!("Recorded {frame_count} frames to {}", directory.display());
+        let (width, height) = dimensions.unwrap_or_default();
+        match format {
+            RecordingFormat::Png => println!("To encode: ffmpeg -framerate {frame_rate} -i frame_%05d.png video.mp4"),
+            RecordingFormat::Raw => println!("To encode: ffmpeg -f rawvideo -pixel_format rgb24 -video_size {width}x{height} -framerate {frame_rate} -i {RAW_STREAM_FILE_NAME} video.mp4")
+        }
+    }
+}
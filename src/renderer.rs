@@ -2,57 +2,85 @@ pub mod renderable_object;
 pub mod mesh;
 pub mod shading;
 pub mod text_rendering;
+pub mod image_file;
 mod render_target;
+mod shadow_map;
 mod world_rendering;
 
 use crate::game::world::Multiverse;
-use crate::global_data::GlobalData;
+use crate::global_data::{GlobalData, VisualMode};
 use glium::Surface;
 use shading::abstract_material::Material;
 use shading::materials;
 use shading::shaders::ShaderProgramContainer;
 use crate::info_screen::render_info_screen;
 use render_target::RenderTarget;
+use shadow_map::{ShadowMap3D, ShadowMap4D};
+use image_file::Image;
+use glium::backend::Facade;
+
+//shared by glium::Display and glium::HeadlessRenderer, so the renderer can draw to a window as well as offscreen
+pub type GlContext = std::rc::Rc<glium::backend::Context>;
 
 pub struct Renderer<'a> {
     shader_programs: ShaderProgramContainer,
     text_renderer: text_rendering::TextRenderer<'a>,
     alternate_target: RenderTarget,
     skeleton_target: RenderTarget,
+    shadow_map_3D: ShadowMap3D,
+    shadow_map_4D: ShadowMap4D,
     VERTICAL_LINE: mesh::StaticUploadedMeshSimple,
+    CROSSHAIR: mesh::StaticUploadedMeshSimple,
     BLIT_QUAD: mesh::StaticUploadedMeshSimple
 }
 impl<'a> Renderer<'a> {
-    pub fn new(display: &glium::Display, global_data: &GlobalData) -> Self {
+    pub fn new(display: &GlContext, global_data: &GlobalData) -> Self {
         Self {
             shader_programs: ShaderProgramContainer::new(display),
             text_renderer: text_rendering::TextRenderer::new(display, global_data),
-            alternate_target: RenderTarget::build(display),
-            skeleton_target: RenderTarget::build(display),
+            alternate_target: RenderTarget::build(display, global_data.resolution.into()),
+            skeleton_target: RenderTarget::build(display, global_data.resolution.into()),
+            shadow_map_3D: ShadowMap3D::new(display, &global_data.options.dev.shadow),
+            shadow_map_4D: ShadowMap4D::new(display, &global_data.options.dev.shadow),
             VERTICAL_LINE: mesh::primitives::vertical_line().upload_static(display),
+            CROSSHAIR: mesh::primitives::crosshair().upload_static(display),
             BLIT_QUAD: mesh::primitives::blit_quad().upload_static(display)
         }
     }
 
     pub fn render_frame(&mut self, display: &glium::Display, multiverse: &Multiverse, global_data: &mut GlobalData) {
         let mut target = display.draw();
+        self.render_onto(&mut target, display.get_context(), multiverse, global_data);
+        self.draw_crosshair(&mut target, global_data);
+        target.finish().unwrap();
+    }
+
+    //for headless mode, renders at global_data.resolution and reads the result back
+    pub fn render_frame_offscreen(&mut self, display: &GlContext, multiverse: &Multiverse, global_data: &GlobalData) -> Image {
+        let mut target = RenderTarget::build(display, global_data.resolution.into());
+        target.with_frame_buffer_mut(|frame_buffer|
+            self.render_onto(frame_buffer, display, multiverse, global_data)
+        );
+
+        Image::from_raw(target.borrow_color_texture().read())
+    }
+
+    fn render_onto<S: Surface>(&mut self, target: &mut S, display: &GlContext, multiverse: &Multiverse, global_data: &GlobalData) {
         target.clear_color_and_depth(
             (0.0, 0.0, 1.0, 1.0),
             1.0
         );
 
-        self.render_objects(&mut target, display, multiverse, global_data);
+        self.render_objects(target, display, multiverse, global_data);
         if global_data.info_screen_visible {
-            render_info_screen(&mut target, display, &mut self.text_renderer, multiverse, global_data);
+            render_info_screen(target, display, &mut self.text_renderer, multiverse, global_data);
         }
-
-        target.finish().unwrap();
     }
 
-    fn render_objects(
+    fn render_objects<S: Surface>(
         &mut self,
-        target: &mut glium::Frame,
-        display: &glium::Display,
+        target: &mut S,
+        display: &GlContext,
         multiverse: &Multiverse,
         global_data: &GlobalData)
     {
@@ -64,7 +92,7 @@ impl<'a> Renderer<'a> {
         }
     }
 
-    fn draw_vertical_line(&self, target: &mut glium::Frame) {
+    fn draw_vertical_line<S: Surface>(&self, target: &mut S) {
         let material = materials::SingleColorScreenSpaceMaterial {
             color: glam::Vec3::new(0.0, 0.0, 0.0)
         };
@@ -78,4 +106,38 @@ impl<'a> Renderer<'a> {
             &draw_parameters
         ).unwrap();
     }
+
+    /* Only in the window, so that headless renders stay free of it. Projection4D views the camera from outside, so there's
+       nowhere the camera looks. In Combined4D it's in the middle of the main slice, not where the viewports meet */
+    fn draw_crosshair<S: Surface>(&self, target: &mut S, global_data: &GlobalData) {
+        let size = global_data.options.user.graphics.crosshair_size;
+        if size == 0 || global_data.visual_mode == VisualMode::Projection4D {
+            return;
+        }
+        let material = materials::SingleColorScreenSpaceMaterial {
+            color: glam::Vec3::new(0.0, 0.0, 0.0)
+        };
+        let (width, height) = target.get_dimensions();
+        let view = match global_data.visual_mode {
+            VisualMode::Combined4D => world_rendering::get_combined_4D_main_viewport((width, height), global_data),
+            _ => glium::Rect { left: 0, bottom: 0, width, height }
+        };
+        let draw_parameters = glium::DrawParameters {
+            viewport: Some(glium::Rect {
+                left: (view.left + view.width / 2).saturating_sub(size / 2),
+                bottom: (view.bottom + view.height / 2).saturating_sub(size / 2),
+                width: size,
+                height: size
+            }),
+            ..Default::default()
+        };
+
+        target.draw(
+            &self.CROSSHAIR.vertices,
+            &self.CROSSHAIR.indeces,
+            self.shader_programs.get_program(materials::SingleColorScreenSpaceMaterial::PROGRAM_IDS.normal_3D),
+            &material.get_uniforms(),
+            &draw_parameters
+        ).unwrap();
+    }
 }
@@ -0,0 +1,38 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+//a frame read back from the GPU, as RGBA8 with the rows ordered from the top, like in image files
+pub struct Image {
+    pub dimensions: (u32, u32),
+    pub pixels: Vec<u8>
+}
+impl Image {
+    //glium reads textures and framebuffers bottom row first
+    pub fn from_raw(raw: glium::texture::RawImage2d<u8>) -> Self {
+        let row_length = raw.width as usize * 4;
+        let pixels = raw.data.chunks_exact(row_length).rev().flatten().copied().collect();
+
+        Self {
+            dimensions: (raw.width, raw.height),
+            pixels
+        }
+    }
+
+    //alpha is dropped, since the window ignores it as well
+    pub fn get_rgb_pixels(&self) -> Vec<u8> {
+        self.pixels.chunks_exact(4).flat_map(|pixel| &pixel[0..3]).copied().collect()
+    }
+
+    pub fn save_png(&self, path: &Path) -> std::io::Result<()> {
+        let rgb_pixels = self.get_rgb_pixels();
+
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.dimensions.0, self.dimensions.1);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&rgb_pixels)?;
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ mod subdivide;
 
 use crate::game::transform::{AffineTransform3D, AffineTransform4D};
 use vertex::*;
+use super::GlContext;
 
 type GpuIndexT = u32;
 
@@ -16,7 +17,7 @@ pub struct SimpleMesh {
     pub topology: glium::index::PrimitiveType
 }
 impl SimpleMesh {
-    pub fn upload_static(&self, display: &glium::Display) -> StaticUploadedMeshSimple {
+    pub fn upload_static(&self, display: &GlContext) -> StaticUploadedMeshSimple {
         StaticUploadedMeshSimple {
             vertices: get_gpu_vertices(display, &self.vertices),
             indeces: get_gpu_indeces_from_flat(display, self.topology, self.indeces.iter().copied())
@@ -48,7 +49,7 @@ impl Mesh3D {
         skeleton_indeces: Vec::new()
     };
 
-    pub fn upload_static(&self, display: &glium::Display) -> StaticUploadedMesh3D {
+    pub fn upload_static(&self, display: &GlContext) -> StaticUploadedMesh3D {
         StaticUploadedMesh3D {
             vertices: get_gpu_vertices(display, &self.vertices),
             indeces: get_gpu_indeces(display, glium::index::PrimitiveType::TrianglesList, &self.indeces),
@@ -80,7 +81,7 @@ impl Mesh4D {
         skeleton_indeces: Vec::new()
     };
 
-    pub fn upload_static(&self, display: &glium::Display) -> StaticUploadedMesh4D {
+    pub fn upload_static(&self, display: &GlContext) -> StaticUploadedMesh4D {
         StaticUploadedMesh4D {
             vertices: get_gpu_vertices(display, &self.vertices),
             indeces: get_gpu_indeces(display, glium::index::PrimitiveType::LinesListAdjacency, &self.indeces),
@@ -122,7 +123,7 @@ pub fn flat_indeces<const N: usize>(nested_indeces: Vec<[usize; N]>) -> Vec<usiz
     nested_indeces.iter().flatten().copied().collect()
 }
 
-fn get_gpu_vertices<V, CV>(display: &glium::Display, cpu_vertices: &Vec<CV>) -> glium::VertexBuffer<V>
+fn get_gpu_vertices<V, CV>(display: &GlContext, cpu_vertices: &Vec<CV>) -> glium::VertexBuffer<V>
     where V: glium::Vertex + From<CV>, CV: Copy
 {
     let vertices: Vec<V> = cpu_vertices.iter()
@@ -132,10 +133,10 @@ fn get_gpu_vertices<V, CV>(display: &glium::Display, cpu_vertices: &Vec<CV>) ->
     glium::VertexBuffer::immutable(display, &vertices).unwrap()
 }
 
-fn get_gpu_indeces<const N: usize>(display: &glium::Display, topology: glium::index::PrimitiveType, cpu_indeces: &Vec<[usize; N]>) -> glium::IndexBuffer<GpuIndexT> {
+fn get_gpu_indeces<const N: usize>(display: &GlContext, topology: glium::index::PrimitiveType, cpu_indeces: &Vec<[usize; N]>) -> glium::IndexBuffer<GpuIndexT> {
     get_gpu_indeces_from_flat(display, topology, cpu_indeces.iter().flatten().copied())
 }
-fn get_gpu_indeces_from_flat(display: &glium::Display, topology: glium::index::PrimitiveType, cpu_indeces: impl Iterator<Item = usize>) -> glium::IndexBuffer<GpuIndexT> {
+fn get_gpu_indeces_from_flat(display: &GlContext, topology: glium::index::PrimitiveType, cpu_indeces: impl Iterator<Item = usize>) -> glium::IndexBuffer<GpuIndexT> {
     let indeces: Vec<GpuIndexT> = cpu_indeces
         .map(|i| i.try_into().expect(&format!("Failed to convert index {} to {}", i, stringify!(GpuIndexT))))
         .collect();
@@ -3,7 +3,8 @@ mod initial_border_pair;
 
 use self::border_pair::*;
 use self::initial_border_pair::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use indexmap::IndexSet;
 use glam::{IVec3, IVec4};
 
 #[derive(Clone, Debug)]
@@ -27,7 +28,7 @@ impl SampleCloud3D {
             (initial_border_pair.B, normalized_function(initial_border_pair.B))
         ]);
         let mut unprocessed_border_pairs = vec![initial_border_pair];
-        let mut all_border_pairs: HashSet<BorderPair3D> = unprocessed_border_pairs.iter().copied().collect();
+        let mut all_border_pairs: IndexSet<BorderPair3D> = unprocessed_border_pairs.iter().copied().collect();
 
         //depth first search
         while !unprocessed_border_pairs.is_empty() {
@@ -68,7 +69,7 @@ impl SampleCloud4D {
             (initial_border_pair.B, normalized_function(initial_border_pair.B))
         ]);
         let mut unprocessed_border_pairs = vec![initial_border_pair];
-        let mut all_border_pairs: HashSet<BorderPair4D> = unprocessed_border_pairs.iter().copied().collect();
+        let mut all_border_pairs: IndexSet<BorderPair4D> = unprocessed_border_pairs.iter().copied().collect();
 
         //depth first search
         while !unprocessed_border_pairs.is_empty() {
@@ -2,7 +2,7 @@ use std::ops::{Add, AddAssign};
 use std::iter::Sum;
 use super::{Mesh3D, Mesh4D};
 use crate::combinations::combinations_constsize_owned;
-use std::collections::HashSet;
+use indexmap::IndexSet;
 
 impl AddAssign for Mesh3D {
     fn add_assign(&mut self, mut rhs: Self) {
@@ -83,8 +83,8 @@ impl Mesh3D {
 impl Mesh4D {
     //a full skeleton contains every edge
     pub fn with_full_skeleton(mut self) -> Self {
-        //collecting to a set removes duplicates
-        let edge_set: HashSet<[usize; 2]> = self.indeces.iter()
+        //collecting to a set removes duplicates. Ordered, so that the same mesh always gets the same skeleton
+        let edge_set: IndexSet<[usize; 2]> = self.indeces.iter()
             .map(|&primitive| combinations_constsize_owned::<2,_,_>(primitive))
             .flatten()
             .collect();
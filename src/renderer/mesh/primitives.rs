@@ -29,6 +29,20 @@ pub fn vertical_line() -> SimpleMesh {
     }
 }
 
+//drawn into a small square viewport, so it keeps its shape at any aspect ratio
+pub fn crosshair() -> SimpleMesh {
+    SimpleMesh {
+        vertices: vec![
+            CpuVertexSimple { position: Vec3::new(-1.0,  0.0, 0.0) },
+            CpuVertexSimple { position: Vec3::new( 1.0,  0.0, 0.0) },
+            CpuVertexSimple { position: Vec3::new( 0.0, -1.0, 0.0) },
+            CpuVertexSimple { position: Vec3::new( 0.0,  1.0, 0.0) }
+        ],
+        indeces: vec![0, 1, 2, 3],
+        topology: glium::index::PrimitiveType::LinesList
+    }
+}
+
 pub fn index_of<T: PartialEq + DebugTrait>(element: T, vec: &Vec<T>) -> usize {
     vec.iter().position(|e| *e == element).expect(&format!("Didn't find {:?}", element))
 }
@@ -2,7 +2,7 @@ use super::{Mesh3D, Mesh4D, EdgeIndeces};
 use glam::{Mat3, Vec3, BVec3, Mat4, Vec4, BVec4A};
 use crate::game::transform::{AffineTransform3D, Transform3D, AffineTransform4D, Transform4D};
 use super::{quad_3D, cube_4D};
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 //gives a cube with width 1 and origo as the center
 pub fn cube_3D() -> Mesh3D {
@@ -33,7 +33,7 @@ pub fn cube_3D() -> Mesh3D {
     let discrete_vertices: Vec<BVec3> = mesh.vertices.iter()
         .map(|&v| v.position.cmpge(Vec3::ZERO))
         .collect();
-    let mut filter_hash_map: HashMap<[BVec3; 1], [usize; 1]> = HashMap::new();
+    let mut filter_hash_map: IndexMap<[BVec3; 1], [usize; 1]> = IndexMap::new();
     for packed_vertex_index in mesh.skeleton_indeces {
         filter_hash_map.insert([discrete_vertices[packed_vertex_index[0]]], packed_vertex_index);
     }
@@ -71,7 +71,7 @@ pub fn tesseract_4D() -> Mesh4D {
     let discrete_vertices: Vec<BVec4A> = mesh.vertices.iter()
         .map(|&v| v.position.cmpge(Vec4::ZERO))
         .collect();
-    let mut filter_hash_map: HashMap<[BVec4A; 2], [usize; 2]> = HashMap::new();
+    let mut filter_hash_map: IndexMap<[BVec4A; 2], [usize; 2]> = IndexMap::new();
     for edge in mesh.skeleton_indeces {
         let ordered_edge: EdgeIndeces = edge.into();
         filter_hash_map.insert([discrete_vertices[ordered_edge.A], discrete_vertices[ordered_edge.B]], edge);
@@ -3,6 +3,7 @@ use super::shading::abstract_material::Material;
 use super::shading::materials;
 use super::shading::shaders::ShaderProgramContainer;
 use super::mesh;
+use super::GlContext;
 use glium::{texture, framebuffer};
 
 #[ouroboros::self_referencing]
@@ -15,9 +16,7 @@ pub struct RenderTarget {
     pub frame_buffer: framebuffer::SimpleFrameBuffer<'this>
 }
 impl RenderTarget {
-    pub fn build(display: &glium::Display) -> RenderTarget {
-        let dimensions = display.get_framebuffer_dimensions();
-
+    pub fn build(display: &GlContext, dimensions: (u32, u32)) -> RenderTarget {
         RenderTargetBuilder {
             depth_texture: texture::DepthTexture2d::empty_with_format(
                 display,
@@ -48,9 +47,10 @@ impl RenderTarget {
         self.borrow_frame_buffer().get_dimensions()
     }
 
-    pub fn setup_for_rendering(&mut self, display: &glium::Display, color: (f32, f32, f32, f32)) {
-        if self.get_dimensions() != display.get_framebuffer_dimensions() {
-            *self = Self::build(display);
+    //dimensions are those of the final target, which isn't always the default framebuffer
+    pub fn setup_for_rendering(&mut self, display: &GlContext, dimensions: (u32, u32), color: (f32, f32, f32, f32)) {
+        if self.get_dimensions() != dimensions {
+            *self = Self::build(display, dimensions);
         }
 
         self.with_frame_buffer_mut(|frame_buffer| {
@@ -61,7 +61,7 @@ impl RenderTarget {
         });
     }
     
-    pub fn blend_onto(&self, target: &mut glium::Frame, degenerate_strength: f32, shaders: &ShaderProgramContainer, blit_quad: &mesh::StaticUploadedMeshSimple) {
+    pub fn blend_onto<S: Surface>(&self, target: &mut S, degenerate_strength: f32, shaders: &ShaderProgramContainer, blit_quad: &mesh::StaticUploadedMeshSimple) {
         let blit_material = materials::BlitMaterial {
             texture: self.borrow_color_texture()
         };
@@ -87,7 +87,12 @@ impl RenderTarget {
         ).unwrap();
     }
 
-    pub fn blit_onto_with_alpha(&self, target: &mut glium::Frame, shaders: &ShaderProgramContainer, blit_quad: &mesh::StaticUploadedMeshSimple) {
+    pub fn blit_onto_with_alpha<S: Surface>(&self, target: &mut S, shaders: &ShaderProgramContainer, blit_quad: &mesh::StaticUploadedMeshSimple) {
+        self.blit_onto_viewport_with_alpha(target, None, shaders, blit_quad);
+    }
+
+    //squeezes the whole texture into the viewport (the whole target if None)
+    pub fn blit_onto_viewport_with_alpha<S: Surface>(&self, target: &mut S, viewport: Option<glium::Rect>, shaders: &ShaderProgramContainer, blit_quad: &mesh::StaticUploadedMeshSimple) {
         let blit_material = materials::BlitMaterial {
             texture: self.borrow_color_texture()
         };
@@ -101,6 +106,7 @@ impl RenderTarget {
                 alpha: glium::BlendingFunction::Max,
                 ..Default::default()
             },
+            viewport,
             ..Default::default()
         };
 
@@ -112,4 +118,32 @@ impl RenderTarget {
             &draw_parameters
         ).unwrap();
     }
+
+    //like blit_onto_with_alpha, with the alpha scaled by opacity
+    pub fn blit_onto_with_opacity<S: Surface>(&self, target: &mut S, opacity: f32, shaders: &ShaderProgramContainer, blit_quad: &mesh::StaticUploadedMeshSimple) {
+        let blit_material = materials::TranslucentBlitMaterial {
+            texture: self.borrow_color_texture(),
+            opacity
+        };
+
+        let draw_parameters = glium::DrawParameters {
+            blend: glium::Blend {
+                color: glium::BlendingFunction::Addition {
+                    source: glium::LinearBlendingFactor::SourceAlpha,
+                    destination: glium::LinearBlendingFactor::OneMinusSourceAlpha
+                },
+                alpha: glium::BlendingFunction::Max,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        target.draw(
+            &blit_quad.vertices,
+            &blit_quad.indeces,
+            shaders.get_program(materials::TranslucentBlitMaterial::PROGRAM_IDS.normal_3D),
+            &blit_material.get_uniforms(),
+            &draw_parameters
+        ).unwrap();
+    }
 }
\ No newline at end of file
@@ -1,25 +1,27 @@
-use super::shading::abstract_material::Material;
+use super::shading::dynamic_material::DynamicMaterial;
 use super::mesh;
+use super::GlContext;
 use crate::game::transform;
 
 use crate::game::transform::{AffineTransform3D, AffineTransform4D};
 use crate::global_data::{GlobalData, VisualMode};
 use super::shading::shaders::ShaderProgramContainer;
-use super::shading::uniform::{GlobalVertexBlock3D, GlobalFragmentBlock3D, GlobalVertexBlock4D, GlobalFragmentBlock4D, UniformBlock};
+use super::shading::uniform::{GlobalVertexBlock3D, GlobalFragmentBlock3D, GlobalVertexBlock4D, GlobalFragmentBlock4D, DrawUniforms3D, DrawUniforms4D, UniformBlock};
 use super::shading::glsl_conversion::ToStd140;
 
-pub struct RenderableObject3D<M: Material> {
+//an entity with everything needed for drawing, borrowed from a scene
+pub struct RenderableObject3D<'a> {
     pub transform: transform::AffineTransform3D,
-    pub mesh: mesh::StaticUploadedMesh3D,
-    pub material: M
+    pub mesh: &'a mesh::StaticUploadedMesh3D,
+    pub material: &'a DynamicMaterial
 }
-pub struct RenderableObject4D<M: Material> {
+pub struct RenderableObject4D<'a> {
     pub transform: transform::AffineTransform4D,
-    pub mesh: mesh::StaticUploadedMesh4D,
-    pub material: M
+    pub mesh: &'a mesh::StaticUploadedMesh4D,
+    pub material: &'a DynamicMaterial
 }
 
-impl<M: Material> RenderableObject3D<M> {
+impl RenderableObject3D<'_> {
     pub fn render<A: glium::Surface, B: glium::Surface>(&self, targets: &mut ObjectDrawTargets<'_, A, B>, context: &ObjectDrawContext3D) {
         let to_world_transform = self.transform;
         let to_view_transform = context.inverse_camera_trs_matrix * to_world_transform;
@@ -33,12 +35,18 @@ impl<M: Material> RenderableObject3D<M> {
             normal_matrix: normal_matrix.std140()
         };
         let vertex_block_buffer = vertex_block.get_glium_uniform_buffer(context.display);
+        let draw_uniforms = DrawUniforms3D {
+            vertex_block: &vertex_block_buffer,
+            fragment_block: &context.fragment_block_buffer,
+            shadow_map: context.shadow_map
+        };
 
+        let program_ids = self.material.program_ids();
         let (surface_program_id, skeleton_program_id) = match context.visual_mode {
-            VisualMode::Normal3D => (M::PROGRAM_IDS.normal_3D, M::PROGRAM_IDS.normal_3D_skeleton),
-            VisualMode::Degenerate3D => (M::PROGRAM_IDS.degenerate_3D, M::PROGRAM_IDS.degenerate_3D_skeleton),
+            VisualMode::Normal3D => (program_ids.normal_3D, program_ids.normal_3D_skeleton),
+            VisualMode::Degenerate3D => (program_ids.degenerate_3D, program_ids.degenerate_3D_skeleton),
             VisualMode::Combined3D => panic!("Cannot handle {:?}. Please render in separate passes.", context.visual_mode),
-            VisualMode::Degenerate4D => panic!("Cannot handle {:?}. Please use the 4D pipeline. ", context.visual_mode)
+            VisualMode::Degenerate4D | VisualMode::ThickSlice4D | VisualMode::Projection4D | VisualMode::Combined4D => panic!("Cannot handle {:?}. Please use the 4D pipeline. ", context.visual_mode)
         };
         let surface_program  = context.shaders.get_program(surface_program_id);
         let skeleton_program = context.shaders.get_program(skeleton_program_id);
@@ -49,8 +57,7 @@ impl<M: Material> RenderableObject3D<M> {
             &self.mesh.vertices,
             &self.mesh.indeces,
             surface_program,
-            &vertex_block_buffer,
-            &context.fragment_block_buffer,
+            &draw_uniforms,
             &context.surface_glium_draw_parameters
         ).unwrap();
 
@@ -60,13 +67,12 @@ impl<M: Material> RenderableObject3D<M> {
             &self.mesh.vertices,
             &self.mesh.skeleton_indeces,
             skeleton_program,
-            &vertex_block_buffer,
-            &context.fragment_block_buffer,
+            &draw_uniforms,
             &context.skeleton_glium_draw_parameters
         ).unwrap();
     }
 }
-impl<M: Material> RenderableObject4D<M> {
+impl RenderableObject4D<'_> {
     pub fn render<A: glium::Surface, B: glium::Surface>(&self, targets: &mut ObjectDrawTargets<'_, A, B>, context: &ObjectDrawContext4D) {
             let to_world_transform = self.transform;
             let to_view_transform = context.inverse_camera_trs_matrix * to_world_transform;
@@ -77,12 +83,25 @@ impl<M: Material> RenderableObject4D<M> {
                 to_world_transform: to_world_transform.std140(),
                 to_view_transform: to_view_transform.std140(),
                 to_clip_transform: to_clip_transform.std140(),
-                normal_matrix: normal_matrix.std140()
+                normal_matrix: normal_matrix.std140(),
+                volume_to_view_transform: context.volume_to_view_transform.std140(),
+                volume_to_clip_transform: context.volume_to_clip_transform.std140()
             };
             let vertex_block_buffer = vertex_block.get_glium_uniform_buffer(context.display);
+            let draw_uniforms = DrawUniforms4D {
+                vertex_block: &vertex_block_buffer,
+                fragment_block: &context.fragment_block_buffer,
+                shadow_map: context.shadow_map
+            };
 
-            let surface_program = context.shaders.get_program(M::PROGRAM_IDS.degenerate_4D);
-            let skeleton_program = context.shaders.get_program(M::PROGRAM_IDS.degenerate_4D_skeleton);
+            let program_ids = self.material.program_ids();
+            let (surface_program_id, skeleton_program_id) = match context.visual_mode {
+                VisualMode::Degenerate4D | VisualMode::ThickSlice4D | VisualMode::Combined4D => (program_ids.degenerate_4D, program_ids.degenerate_4D_skeleton),
+                VisualMode::Projection4D => (program_ids.projected_4D, program_ids.projected_4D_skeleton),
+                _ => panic!("Cannot handle {:?}. Please use the 3D pipeline. ", context.visual_mode)
+            };
+            let surface_program = context.shaders.get_program(surface_program_id);
+            let skeleton_program = context.shaders.get_program(skeleton_program_id);
 
             //surface
             self.material.draw_mesh_4D(
@@ -90,8 +109,7 @@ impl<M: Material> RenderableObject4D<M> {
                 &self.mesh.vertices,
                 &self.mesh.indeces,
                 surface_program,
-                &vertex_block_buffer,
-                &context.fragment_block_buffer,
+                &draw_uniforms,
                 &context.surface_glium_draw_parameters
             ).unwrap();
 
@@ -101,32 +119,37 @@ impl<M: Material> RenderableObject4D<M> {
                 &self.mesh.vertices,
                 &self.mesh.skeleton_indeces,
                 skeleton_program,
-                &vertex_block_buffer,
-                &context.fragment_block_buffer,
+                &draw_uniforms,
                 &context.skeleton_glium_draw_parameters
             ).unwrap();
     }
 }
 
 pub struct ObjectDrawContext3D<'a> {
-    pub display: &'a glium::Display,
+    pub display: &'a GlContext,
     pub shaders: &'a ShaderProgramContainer,
     pub inverse_camera_trs_matrix: AffineTransform3D,
     pub projection_matrix: AffineTransform3D,
     pub fragment_block_buffer: glium::uniforms::UniformBuffer<GlobalFragmentBlock3D>,
+    pub shadow_map: &'a glium::texture::DepthTexture2d,
     pub surface_glium_draw_parameters: glium::DrawParameters<'a>,
     pub skeleton_glium_draw_parameters: glium::DrawParameters<'a>,
     pub visual_mode: VisualMode,
     pub _global_data: &'a GlobalData
 }
 pub struct ObjectDrawContext4D<'a> {
-    pub display: &'a glium::Display,
+    pub display: &'a GlContext,
     pub shaders: &'a ShaderProgramContainer,
     pub inverse_camera_trs_matrix: AffineTransform4D,
     pub projection_matrix: AffineTransform4D,
     pub fragment_block_buffer: glium::uniforms::UniformBuffer<GlobalFragmentBlock4D>,
+    pub shadow_map: &'a glium::texture::DepthTexture2dArray,
     pub surface_glium_draw_parameters: glium::DrawParameters<'a>,
     pub skeleton_glium_draw_parameters: glium::DrawParameters<'a>,
+    pub visual_mode: VisualMode,
+    //for looking at the projected volume in Projection4D
+    pub volume_to_view_transform: AffineTransform3D,
+    pub volume_to_clip_transform: AffineTransform3D,
     pub _global_data: &'a GlobalData
 }
 
@@ -2,4 +2,5 @@ pub mod materials;
 pub mod uniform;
 pub mod shaders;
 pub mod abstract_material;
+pub mod dynamic_material;
 pub mod glsl_conversion;
\ No newline at end of file
@@ -10,15 +10,15 @@ macro_rules! implement_material_draw { ($get_uniforms_func:expr) => {
         vertices: V,
         indeces: I,
         program: &glium::Program,
-        vertex_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalVertexBlock3D>,
-        fragment_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalFragmentBlock3D>,
+        draw_uniforms: &crate::renderer::shading::uniform::DrawUniforms3D<'_>,
         draw_parameters: &glium::DrawParameters<'_>)
         -> Result<(), glium::DrawError>
         where T: glium::Surface, V: glium::vertex::MultiVerticesSource<'b>, I: Into<glium::index::IndicesSource<'a>>
     {
         let uniforms = $get_uniforms_func(self);
-        let uniforms = uniforms.add("vertex_uniforms", vertex_block);
-        let uniforms = uniforms.add("fragment_uniforms", fragment_block);
+        let uniforms = uniforms.add("vertex_uniforms", draw_uniforms.vertex_block);
+        let uniforms = uniforms.add("fragment_uniforms", draw_uniforms.fragment_block);
+        let uniforms = uniforms.add("shadow_map", crate::renderer::shading::abstract_material::get_shadow_map_sampler(draw_uniforms.shadow_map));
 
         target.draw(vertices, indeces, program, &uniforms, draw_parameters)
     }
@@ -29,15 +29,15 @@ macro_rules! implement_material_draw { ($get_uniforms_func:expr) => {
         vertices: V,
         indeces: I,
         program: &glium::Program,
-        vertex_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalVertexBlock4D>,
-        fragment_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalFragmentBlock4D>,
+        draw_uniforms: &crate::renderer::shading::uniform::DrawUniforms4D<'_>,
         draw_parameters: &glium::DrawParameters<'_>)
         -> Result<(), glium::DrawError>
         where T: glium::Surface, V: glium::vertex::MultiVerticesSource<'b>, I: Into<glium::index::IndicesSource<'a>>
     {
         let uniforms = $get_uniforms_func(self);
-        let uniforms = uniforms.add("vertex_uniforms", vertex_block);
-        let uniforms = uniforms.add("fragment_uniforms", fragment_block);
+        let uniforms = uniforms.add("vertex_uniforms", draw_uniforms.vertex_block);
+        let uniforms = uniforms.add("fragment_uniforms", draw_uniforms.fragment_block);
+        let uniforms = uniforms.add("shadow_map", crate::renderer::shading::abstract_material::get_shadow_map_sampler(draw_uniforms.shadow_map));
 
         target.draw(vertices, indeces, program, &uniforms, draw_parameters)
     }
@@ -46,6 +46,14 @@ macro_rules! any_uniforms_storage {() => { glium::uniforms::UniformsStorage<impl
 pub(crate) use implement_material_draw;
 pub(crate) use any_uniforms_storage;
 
+//shadow maps store depth, so interpolating between texels makes no sense
+pub fn get_shadow_map_sampler<T>(shadow_map: &T) -> glium::uniforms::Sampler<'_, T> {
+    glium::uniforms::Sampler::new(shadow_map)
+        .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest)
+        .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest)
+        .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+}
+
 pub trait Material {
     const PROGRAM_DESCRIPTORS: ProgramDescriptorGroup;
     const PROGRAM_IDS: ShaderProgramIdGroup;
@@ -56,8 +64,7 @@ pub trait Material {
         vertices: V,
         indeces: I,
         program: &glium::Program,
-        vertex_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalVertexBlock3D>,
-        fragment_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalFragmentBlock3D>,
+        draw_uniforms: &crate::renderer::shading::uniform::DrawUniforms3D<'_>,
         draw_parameters: &glium::DrawParameters<'_>)
         -> Result<(), glium::DrawError>
         where T: glium::Surface, V: glium::vertex::MultiVerticesSource<'b>, I: Into<glium::index::IndicesSource<'a>>;
@@ -68,8 +75,7 @@ pub trait Material {
         vertices: V,
         indeces: I,
         program: &glium::Program,
-        vertex_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalVertexBlock4D>,
-        fragment_block: &glium::uniforms::UniformBuffer<crate::renderer::shading::uniform::GlobalFragmentBlock4D>,
+        draw_uniforms: &crate::renderer::shading::uniform::DrawUniforms4D<'_>,
         draw_parameters: &glium::DrawParameters<'_>)
         -> Result<(), glium::DrawError>
         where T: glium::Surface, V: glium::vertex::MultiVerticesSource<'b>, I: Into<glium::index::IndicesSource<'a>>;
@@ -83,7 +89,9 @@ pub struct ShaderProgramIdGroup {
     pub degenerate_3D: ShaderProgramId,
     pub degenerate_3D_skeleton: ShaderProgramId,
     pub degenerate_4D: ShaderProgramId,
-    pub degenerate_4D_skeleton: ShaderProgramId
+    pub degenerate_4D_skeleton: ShaderProgramId,
+    pub projected_4D: ShaderProgramId,
+    pub projected_4D_skeleton: ShaderProgramId
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -93,7 +101,9 @@ pub struct ProgramDescriptorGroup {
     pub degenerate_3D: ProgramDescriptor,
     pub degenerate_3D_skeleton: ProgramDescriptor,
     pub degenerate_4D: ProgramDescriptor,
-    pub degenerate_4D_skeleton: ProgramDescriptor
+    pub degenerate_4D_skeleton: ProgramDescriptor,
+    pub projected_4D: ProgramDescriptor,
+    pub projected_4D_skeleton: ProgramDescriptor
 }
 impl ProgramDescriptorGroup {
     /// assigns same descriptor to all fields
@@ -104,7 +114,9 @@ impl ProgramDescriptorGroup {
             degenerate_3D: descriptor,
             degenerate_3D_skeleton: descriptor,
             degenerate_4D: descriptor,
-            degenerate_4D_skeleton: descriptor
+            degenerate_4D_skeleton: descriptor,
+            projected_4D: descriptor,
+            projected_4D_skeleton: descriptor
         }
     }
 }
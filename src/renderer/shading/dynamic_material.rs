@@ -0,0 +1,73 @@
+use super::abstract_material::{Material, ShaderProgramIdGroup};
+use super::materials::{SingleColorMaterial, ChessboardMaterial, PbrMaterial};
+use super::uniform::{DrawUniforms3D, DrawUniforms4D};
+
+// Material has associated consts and generic methods, so it can't be used as a trait object.
+// This enum wraps every scene material instead, allowing a single list to contain any mix of them.
+#[derive(Debug, Copy, Clone)]
+pub enum DynamicMaterial {
+    SingleColor(SingleColorMaterial),
+    Chessboard(ChessboardMaterial),
+    Pbr(PbrMaterial)
+}
+impl DynamicMaterial {
+    pub fn program_ids(&self) -> ShaderProgramIdGroup {
+        match self {
+            Self::SingleColor(_) => SingleColorMaterial::PROGRAM_IDS,
+            Self::Chessboard(_) => ChessboardMaterial::PROGRAM_IDS,
+            Self::Pbr(_) => PbrMaterial::PROGRAM_IDS
+        }
+    }
+
+    pub fn draw_mesh_3D<'a, 'b, T, V, I>(
+        &self,
+        target: &mut T,
+        vertices: V,
+        indeces: I,
+        program: &glium::Program,
+        draw_uniforms: &DrawUniforms3D<'_>,
+        draw_parameters: &glium::DrawParameters<'_>)
+        -> Result<(), glium::DrawError>
+        where T: glium::Surface, V: glium::vertex::MultiVerticesSource<'b>, I: Into<glium::index::IndicesSource<'a>>
+    {
+        match self {
+            Self::SingleColor(material) => material.draw_mesh_3D(target, vertices, indeces, program, draw_uniforms, draw_parameters),
+            Self::Chessboard(material) => material.draw_mesh_3D(target, vertices, indeces, program, draw_uniforms, draw_parameters),
+            Self::Pbr(material) => material.draw_mesh_3D(target, vertices, indeces, program, draw_uniforms, draw_parameters)
+        }
+    }
+
+    pub fn draw_mesh_4D<'a, 'b, T, V, I>(
+        &self,
+        target: &mut T,
+        vertices: V,
+        indeces: I,
+        program: &glium::Program,
+        draw_uniforms: &DrawUniforms4D<'_>,
+        draw_parameters: &glium::DrawParameters<'_>)
+        -> Result<(), glium::DrawError>
+        where T: glium::Surface, V: glium::vertex::MultiVerticesSource<'b>, I: Into<glium::index::IndicesSource<'a>>
+    {
+        match self {
+            Self::SingleColor(material) => material.draw_mesh_4D(target, vertices, indeces, program, draw_uniforms, draw_parameters),
+            Self::Chessboard(material) => material.draw_mesh_4D(target, vertices, indeces, program, draw_uniforms, draw_parameters),
+            Self::Pbr(material) => material.draw_mesh_4D(target, vertices, indeces, program, draw_uniforms, draw_parameters)
+        }
+    }
+}
+
+impl From<SingleColorMaterial> for DynamicMaterial {
+    fn from(material: SingleColorMaterial) -> Self {
+        Self::SingleColor(material)
+    }
+}
+impl From<ChessboardMaterial> for DynamicMaterial {
+    fn from(material: ChessboardMaterial) -> Self {
+        Self::Chessboard(material)
+    }
+}
+impl From<PbrMaterial> for DynamicMaterial {
+    fn from(material: PbrMaterial) -> Self {
+        Self::Pbr(material)
+    }
+}
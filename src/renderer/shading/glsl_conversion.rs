@@ -25,6 +25,18 @@ impl ToStd140<std140::float> for f32 {
     }
 }
 
+impl ToStd140<std140::int> for i32 {
+    fn std140(&self) -> std140::int {
+        std140::int(*self)
+    }
+}
+
+impl<T: std140::Std140ArrayElement + Copy, const N: usize> ToStd140<std140::array<T, N>> for [T; N] {
+    fn std140(&self) -> std140::array<T, N> {
+        std140::array::from_wrapped(self.map(|element| std140::ArrayElementWrapper { element }))
+    }
+}
+
 impl ToStd140<std140::vec3> for glam::Vec3 {
     fn std140(&self) -> std140::vec3 {
         std140::vec3(self.x, self.y, self.z)
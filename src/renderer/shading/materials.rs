@@ -1,12 +1,15 @@
 use super::abstract_material::{Material, ShaderProgramId, ShaderProgramIdGroup, ProgramDescriptor, ProgramDescriptorGroup, implement_material_draw, any_uniforms_storage};
 use glam::Vec3;
 
-const PROGRAM_DESCRIPTOR_GROUP_COUNT: usize = 4;
+const PROGRAM_DESCRIPTOR_GROUP_COUNT: usize = 7;
 pub const PROGRAM_DESCRIPTOR_GROUPS: [ProgramDescriptorGroup; PROGRAM_DESCRIPTOR_GROUP_COUNT] = [
     SingleColorMaterial::PROGRAM_DESCRIPTORS,
     ChessboardMaterial::PROGRAM_DESCRIPTORS,
+    PbrMaterial::PROGRAM_DESCRIPTORS,
     BlitMaterial::PROGRAM_DESCRIPTORS,
-    SingleColorScreenSpaceMaterial::PROGRAM_DESCRIPTORS
+    TranslucentBlitMaterial::PROGRAM_DESCRIPTORS,
+    SingleColorScreenSpaceMaterial::PROGRAM_DESCRIPTORS,
+    ShadowCasterMaterial::PROGRAM_DESCRIPTORS
 ];
 
 #[derive(Debug, Copy, Clone)]
@@ -26,7 +29,11 @@ impl Material for SingleColorMaterial {
         degenerate_4D: ProgramDescriptor::new_with_geometry(
             "4D/pre_geometry.vert", "4D/single_color.frag", "4D/sliced.geom"),
         degenerate_4D_skeleton: ProgramDescriptor::new(
-            "4D/pre_fragment.vert", "4D/single_color_skeleton.frag")
+            "4D/pre_fragment.vert", "4D/single_color_skeleton.frag"),
+        projected_4D: ProgramDescriptor::new_with_geometry(
+            "4D/projected.vert", "4D/single_color.frag", "4D/projected.geom"),
+        projected_4D_skeleton: ProgramDescriptor::new_with_geometry(
+            "4D/projected.vert", "4D/single_color_skeleton.frag", "4D/projected_skeleton.geom")
     };
     
     const PROGRAM_IDS: ShaderProgramIdGroup = get_program_id_container::<Self>();
@@ -59,7 +66,11 @@ impl Material for ChessboardMaterial {
         degenerate_4D: ProgramDescriptor::new_with_geometry(
             "4D/pre_geometry.vert", "4D/chessboard.frag", "4D/sliced.geom"),
         degenerate_4D_skeleton: ProgramDescriptor::new(
-            "4D/pre_fragment.vert", "4D/chessboard_skeleton.frag")
+            "4D/pre_fragment.vert", "4D/chessboard_skeleton.frag"),
+        projected_4D: ProgramDescriptor::new_with_geometry(
+            "4D/projected.vert", "4D/chessboard.frag", "4D/projected.geom"),
+        projected_4D_skeleton: ProgramDescriptor::new_with_geometry(
+            "4D/projected.vert", "4D/chessboard_skeleton.frag", "4D/projected_skeleton.geom")
     };
     
     const PROGRAM_IDS: ShaderProgramIdGroup = get_program_id_container::<Self>();
@@ -75,6 +86,46 @@ impl ChessboardMaterial {
     }
 }
 
+//Cook-Torrance shading, with specular highlights that make the curvature of 4D objects easier to read
+#[derive(Debug, Copy, Clone)]
+pub struct PbrMaterial {
+    pub albedo_color: Vec3,
+    pub roughness: f32,//0 is a perfect mirror, 1 is fully matte
+    pub metalness: f32
+}
+impl Material for PbrMaterial {
+    const PROGRAM_DESCRIPTORS: ProgramDescriptorGroup = ProgramDescriptorGroup {
+        normal_3D: ProgramDescriptor::new(
+            "3D/pre_fragment.vert", "3D/pbr.frag"),
+        normal_3D_skeleton: ProgramDescriptor::new(
+            "3D/pre_fragment.vert", "3D/pbr_skeleton.frag"),
+        degenerate_3D: ProgramDescriptor::new_with_geometry(
+            "3D/pre_geometry.vert", "3D/pbr.frag", "3D/sliced.geom"),
+        degenerate_3D_skeleton: ProgramDescriptor::new_with_geometry(
+            "3D/pre_geometry.vert", "3D/pbr_skeleton.frag", "3D/skeleton.geom"),
+        degenerate_4D: ProgramDescriptor::new_with_geometry(
+            "4D/pre_geometry.vert", "4D/pbr.frag", "4D/sliced.geom"),
+        degenerate_4D_skeleton: ProgramDescriptor::new(
+            "4D/pre_fragment.vert", "4D/pbr_skeleton.frag"),
+        projected_4D: ProgramDescriptor::new_with_geometry(
+            "4D/projected.vert", "4D/pbr.frag", "4D/projected.geom"),
+        projected_4D_skeleton: ProgramDescriptor::new_with_geometry(
+            "4D/projected.vert", "4D/pbr_skeleton.frag", "4D/projected_skeleton.geom")
+    };
+
+    const PROGRAM_IDS: ShaderProgramIdGroup = get_program_id_container::<Self>();
+    implement_material_draw!(Self::get_uniforms);
+}
+impl PbrMaterial {
+    fn get_uniforms(&self) -> any_uniforms_storage!() {
+        glium::uniform! {
+            albedo: self.albedo_color.to_array(),
+            roughness: self.roughness,
+            metalness: self.metalness
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct BlitMaterial<'a> {
     pub texture: &'a glium::texture::Texture2d
@@ -93,6 +144,26 @@ impl BlitMaterial<'_> {
     }
 }
 
+//scales the alpha of the texture
+pub struct TranslucentBlitMaterial<'a> {
+    pub texture: &'a glium::texture::Texture2d,
+    pub opacity: f32
+}
+impl Material for TranslucentBlitMaterial<'_> {
+    const PROGRAM_DESCRIPTORS: ProgramDescriptorGroup = ProgramDescriptorGroup::new_trivial(ProgramDescriptor::new(
+        "simple/blit.vert", "simple/translucent_blit.frag"));
+    const PROGRAM_IDS: ShaderProgramIdGroup = get_program_id_container::<Self>();
+    implement_material_draw!(Self::get_uniforms);
+}
+impl TranslucentBlitMaterial<'_> {
+    pub fn get_uniforms(&self) -> any_uniforms_storage!() {
+        glium::uniform! {
+            texture_to_blit: self.texture,
+            opacity: self.opacity
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct SingleColorScreenSpaceMaterial {
     pub color: Vec3
@@ -111,8 +182,43 @@ impl SingleColorScreenSpaceMaterial {
     }
 }
 
+//only writes depth, for rendering shadow maps. Shadow maps are orthographic, so the programs are used without perspective division
+#[derive(Debug, Copy, Clone)]
+pub struct ShadowCasterMaterial {
+    pub depth_bias: f32
+}
+impl Material for ShadowCasterMaterial {
+    const PROGRAM_DESCRIPTORS: ProgramDescriptorGroup = ProgramDescriptorGroup {
+        normal_3D: ProgramDescriptor::new(
+            "3D/pre_fragment.vert", "simple/depth_only.frag"),
+        normal_3D_skeleton: ProgramDescriptor::new(
+            "3D/pre_fragment.vert", "simple/depth_only.frag"),
+        degenerate_3D: ProgramDescriptor::new_with_geometry(
+            "3D/pre_geometry.vert", "simple/depth_only.frag", "3D/sliced.geom"),
+        degenerate_3D_skeleton: ProgramDescriptor::new_with_geometry(
+            "3D/pre_geometry.vert", "simple/depth_only.frag", "3D/sliced.geom"),
+        degenerate_4D: ProgramDescriptor::new_with_geometry(
+            "4D/pre_geometry.vert", "simple/depth_only.frag", "4D/sliced.geom"),
+        degenerate_4D_skeleton: ProgramDescriptor::new_with_geometry(
+            "4D/pre_geometry.vert", "simple/depth_only.frag", "4D/sliced.geom"),
+        projected_4D: ProgramDescriptor::new_with_geometry(
+            "4D/pre_geometry.vert", "simple/depth_only.frag", "4D/sliced.geom"),
+        projected_4D_skeleton: ProgramDescriptor::new_with_geometry(
+            "4D/pre_geometry.vert", "simple/depth_only.frag", "4D/sliced.geom")
+    };
+    const PROGRAM_IDS: ShaderProgramIdGroup = get_program_id_container::<Self>();
+    implement_material_draw!(Self::get_uniforms);
+}
+impl ShadowCasterMaterial {
+    pub fn get_uniforms(&self) -> any_uniforms_storage!() {
+        glium::uniform! {
+            depth_bias: self.depth_bias
+        }
+    }
+}
+
 
-const PROGRAM_DESCRIPTORS_PER_GROUP: usize = 6;
+const PROGRAM_DESCRIPTORS_PER_GROUP: usize = 8;
 pub const PROGRAM_DESCRIPTORS: [ProgramDescriptor; PROGRAM_DESCRIPTOR_GROUP_COUNT * PROGRAM_DESCRIPTORS_PER_GROUP] = {
     let mut descriptors = [ProgramDescriptor::new("<null>", "<null>"); PROGRAM_DESCRIPTOR_GROUP_COUNT * PROGRAM_DESCRIPTORS_PER_GROUP];
     let mut i = 0;
@@ -123,6 +229,8 @@ pub const PROGRAM_DESCRIPTORS: [ProgramDescriptor; PROGRAM_DESCRIPTOR_GROUP_COUN
         descriptors[i * PROGRAM_DESCRIPTORS_PER_GROUP + 3] = PROGRAM_DESCRIPTOR_GROUPS[i].degenerate_3D_skeleton;
         descriptors[i * PROGRAM_DESCRIPTORS_PER_GROUP + 4] = PROGRAM_DESCRIPTOR_GROUPS[i].degenerate_4D;
         descriptors[i * PROGRAM_DESCRIPTORS_PER_GROUP + 5] = PROGRAM_DESCRIPTOR_GROUPS[i].degenerate_4D_skeleton;
+        descriptors[i * PROGRAM_DESCRIPTORS_PER_GROUP + 6] = PROGRAM_DESCRIPTOR_GROUPS[i].projected_4D;
+        descriptors[i * PROGRAM_DESCRIPTORS_PER_GROUP + 7] = PROGRAM_DESCRIPTOR_GROUPS[i].projected_4D_skeleton;
         i += 1;
     }
     descriptors
@@ -135,7 +243,9 @@ const fn get_program_id_container<M: Material>() -> ShaderProgramIdGroup {
         degenerate_3D:          get_program_id(M::PROGRAM_DESCRIPTORS.degenerate_3D),
         degenerate_3D_skeleton: get_program_id(M::PROGRAM_DESCRIPTORS.degenerate_3D_skeleton),
         degenerate_4D:          get_program_id(M::PROGRAM_DESCRIPTORS.degenerate_4D),
-        degenerate_4D_skeleton: get_program_id(M::PROGRAM_DESCRIPTORS.degenerate_4D_skeleton)
+        degenerate_4D_skeleton: get_program_id(M::PROGRAM_DESCRIPTORS.degenerate_4D_skeleton),
+        projected_4D:           get_program_id(M::PROGRAM_DESCRIPTORS.projected_4D),
+        projected_4D_skeleton:  get_program_id(M::PROGRAM_DESCRIPTORS.projected_4D_skeleton)
     }
 }
 
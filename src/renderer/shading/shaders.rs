@@ -2,6 +2,7 @@ use std::fs;
 use super::abstract_material::{ShaderProgramId, ProgramDescriptor};
 use super::materials::PROGRAM_DESCRIPTORS;
 use itertools::Itertools;
+use crate::renderer::GlContext;
 
 //regex, that is only compiled once
 macro_rules! regex {
@@ -15,7 +16,7 @@ pub struct ShaderProgramContainer {
     programs: Vec<glium::Program>
 }
 impl ShaderProgramContainer {
-    pub fn new(display: &glium::Display) -> Self {
+    pub fn new(display: &GlContext) -> Self {
         let programs: Vec<glium::Program> = PROGRAM_DESCRIPTORS
             .iter()
             .map(|desc| get_shader_program(&display, desc))
@@ -33,7 +34,7 @@ impl ShaderProgramContainer {
     }
 }
 
-fn get_shader_program(display: &glium::Display, descriptor: &ProgramDescriptor) -> glium::Program {
+fn get_shader_program(display: &GlContext, descriptor: &ProgramDescriptor) -> glium::Program {
     let vertex_src = read_shader_source(descriptor.vertex_shader_path);
     let fragment_src = read_shader_source(descriptor.fragment_shader_path);
     let geometry_src = descriptor.geometry_shader_path.map(|path| read_shader_source(path));
@@ -1,5 +1,6 @@
 use super::glsl_conversion::{Std140AffineTransform3D, Std140AffineTransform4D};
 use std140;
+use crate::renderer::GlContext;
 
 #[std140::repr_std140]
 #[derive(Debug, Clone, Copy)]
@@ -15,30 +16,94 @@ pub struct GlobalVertexBlock4D {
     pub to_world_transform: Std140AffineTransform4D,
     pub to_view_transform: Std140AffineTransform4D,
     pub to_clip_transform: Std140AffineTransform4D,
-    pub normal_matrix: std140::mat4x4
+    pub normal_matrix: std140::mat4x4,
+    pub volume_to_view_transform: Std140AffineTransform3D,
+    pub volume_to_clip_transform: Std140AffineTransform3D
+}
+
+//must match the array sizes in the lighting.glsl files
+pub const MAX_POINT_LIGHTS: usize = 8;
+pub const MAX_DIRECTIONAL_LIGHTS: usize = 4;
+
+#[std140::repr_std140]
+#[derive(Debug, Clone, Copy)]
+pub struct Std140PointLight3D {
+    pub position: std140::vec3,
+    pub color: std140::vec3,
+    pub linear_attenuation: std140::float,
+    pub quadratic_attenuation: std140::float
+}
+#[std140::repr_std140]
+#[derive(Debug, Clone, Copy)]
+pub struct Std140PointLight4D {
+    pub position: std140::vec4,
+    pub color: std140::vec3,
+    pub linear_attenuation: std140::float,
+    pub quadratic_attenuation: std140::float
+}
+#[std140::repr_std140]
+#[derive(Debug, Clone, Copy)]
+pub struct Std140DirectionalLight3D {
+    pub direction: std140::vec3,
+    pub color: std140::vec3
+}
+#[std140::repr_std140]
+#[derive(Debug, Clone, Copy)]
+pub struct Std140DirectionalLight4D {
+    pub direction: std140::vec4,
+    pub color: std140::vec3
 }
 
 #[std140::repr_std140]
 #[derive(Debug, Clone, Copy)]
 pub struct GlobalFragmentBlock3D {
-    pub light_position: std140::vec3,
-    pub light_color: std140::vec3,
+    pub point_lights: std140::array<Std140PointLight3D, MAX_POINT_LIGHTS>,
+    pub directional_lights: std140::array<Std140DirectionalLight3D, MAX_DIRECTIONAL_LIGHTS>,
+    pub shadow_transform: Std140AffineTransform3D,
+    pub camera_position: std140::vec3,
     pub light_ambient_color: std140::vec3,
-    pub light_linear_attenuation: std140::float,
-    pub light_quadratic_attenuation: std140::float
+    pub point_light_count: std140::int,
+    pub directional_light_count: std140::int,
+    pub has_shadow: std140::int
 }
 #[std140::repr_std140]
 #[derive(Debug, Clone, Copy)]
 pub struct GlobalFragmentBlock4D {
-    pub light_position: std140::vec4,
-    pub light_color: std140::vec3,
+    pub point_lights: std140::array<Std140PointLight4D, MAX_POINT_LIGHTS>,
+    pub directional_lights: std140::array<Std140DirectionalLight4D, MAX_DIRECTIONAL_LIGHTS>,
+    pub shadow_transform: Std140AffineTransform4D,
+    pub camera_position: std140::vec4,
     pub light_ambient_color: std140::vec3,
-    pub light_linear_attenuation: std140::float,
-    pub light_quadratic_attenuation: std140::float
+    pub point_light_count: std140::int,
+    pub directional_light_count: std140::int,
+    pub has_shadow: std140::int,
+    pub slice_normal: std140::vec4,
+    pub fog_color: std140::vec3,
+    pub slice_tint_positive_color: std140::vec3,
+    pub slice_tint_negative_color: std140::vec3,
+    pub fog_start: std140::float,
+    pub fog_end: std140::float,
+    pub slice_tint_distance: std140::float,
+    pub slice_tint_strength: std140::float,
+    pub fog_enabled: std140::int,
+    pub slice_tint_enabled: std140::int,
+    pub slice_offset: std140::float
+}
+
+//the uniforms every material gets on top of its own
+pub struct DrawUniforms3D<'a> {
+    pub vertex_block: &'a glium::uniforms::UniformBuffer<GlobalVertexBlock3D>,
+    pub fragment_block: &'a glium::uniforms::UniformBuffer<GlobalFragmentBlock3D>,
+    pub shadow_map: &'a glium::texture::DepthTexture2d
+}
+pub struct DrawUniforms4D<'a> {
+    pub vertex_block: &'a glium::uniforms::UniformBuffer<GlobalVertexBlock4D>,
+    pub fragment_block: &'a glium::uniforms::UniformBuffer<GlobalFragmentBlock4D>,
+    pub shadow_map: &'a glium::texture::DepthTexture2dArray
 }
 
 pub trait UniformBlock {
-    fn get_glium_uniform_buffer(self, display: &glium::Display) -> glium::uniforms::UniformBuffer<Self> where Self: std::marker::Copy {
+    fn get_glium_uniform_buffer(self, display: &GlContext) -> glium::uniforms::UniformBuffer<Self> where Self: std::marker::Copy {
         glium::uniforms::UniformBuffer::new(display, self).unwrap()
     }
 }
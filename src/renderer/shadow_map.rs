@@ -0,0 +1,242 @@
+use glium::{texture, framebuffer, Surface};
+use glam::{Vec3, Mat3, Vec4, Mat4};
+use crate::game::transform::{AffineTransform3D, AffineTransform4D};
+use crate::game::world::{World3D, World4D};
+use crate::game::entity::EntityId;
+use crate::game::light::{Light3D, Light4D};
+use crate::options::ShadowOptions;
+use super::shading::abstract_material::Material;
+use super::shading::materials::ShadowCasterMaterial;
+use super::shading::shaders::ShaderProgramContainer;
+use super::shading::uniform::{GlobalVertexBlock3D, GlobalVertexBlock4D, UniformBlock};
+use super::shading::glsl_conversion::ToStd140;
+use super::GlContext;
+
+/* Orthographic shadow maps of a directional light, covering a box around the camera.
+   In 4D, the light projects the world onto a 3D hyperplane, so the shadow map is a 3D depth texture (stored as layers).
+   Each layer is rendered from a slice of the world, which is taken the same way as the slice the player sees. */
+pub struct ShadowMap3D {
+    pub texture: texture::DepthTexture2d
+}
+pub struct ShadowMap4D {
+    pub texture: texture::DepthTexture2dArray
+}
+
+//the light that casts shadows, and the transform from world space to shadow map coordinates
+#[derive(Debug, Copy, Clone)]
+pub struct Shadow3D {
+    pub light: EntityId,
+    pub shadow_map_transform: AffineTransform3D
+}
+#[derive(Debug, Copy, Clone)]
+pub struct Shadow4D {
+    pub light: EntityId,
+    pub shadow_map_transform: AffineTransform4D
+}
+
+impl ShadowMap3D {
+    pub fn new(display: &GlContext, options: &ShadowOptions) -> Self {
+        Self {
+            texture: texture::DepthTexture2d::empty_with_format(
+                display,
+                texture::DepthFormat::I24,
+                texture::MipmapsOption::NoMipmap,
+                options.resolution,
+                options.resolution
+            ).unwrap()
+        }
+    }
+
+    pub fn render(&mut self, display: &GlContext, shaders: &ShaderProgramContainer, world: &World3D, options: &ShadowOptions) -> Option<Shadow3D> {
+        if !options.enabled {
+            return None;
+        }
+        let (light, direction) = find_shadow_caster_3D(world)?;
+
+        if self.texture.dimensions() != (options.resolution, options.resolution) {
+            *self = Self::new(display, options);
+        }
+
+        let light_projection = get_light_projection_3D(direction, world.player.get_camera_world_position(), options);
+        let shadow_caster = ShadowCasterMaterial {
+            depth_bias: get_depth_bias(options)
+        };
+        let program = shaders.get_program(ShadowCasterMaterial::PROGRAM_IDS.normal_3D);
+        let draw_parameters = get_draw_parameters();
+
+        let mut frame_buffer = framebuffer::SimpleFrameBuffer::depth_only(display, &self.texture).unwrap();
+        frame_buffer.clear_depth(1.0);
+        for (entity, mesh) in world.scene.meshes.iter() {
+            let Some(transform) = world.scene.transforms.get(entity) else {
+                continue;
+            };
+            let to_world_transform: AffineTransform3D = transform.into();
+
+            let vertex_block = GlobalVertexBlock3D {
+                to_world_transform: to_world_transform.std140(),
+                to_view_transform: AffineTransform3D { linear_transform: Mat3::ZERO, translation: Vec3::Z }.std140(),//no perspective division
+                to_clip_transform: (light_projection * to_world_transform).std140(),
+                normal_matrix: to_world_transform.point_transform_to_normal_transform().std140()
+            };
+            let vertex_block_buffer = vertex_block.get_glium_uniform_buffer(display);
+
+            frame_buffer.draw(
+                &mesh.vertices,
+                &mesh.indeces,
+                program,
+                &shadow_caster.get_uniforms().add("vertex_uniforms", &vertex_block_buffer),
+                &draw_parameters
+            ).unwrap();
+        }
+
+        //from [-1, 1] to [0, 1]
+        let to_texture_coordinates = AffineTransform3D {
+            linear_transform: Mat3::from_diagonal(Vec3::splat(0.5)),
+            translation: Vec3::splat(0.5)
+        };
+        Some(Shadow3D {
+            light,
+            shadow_map_transform: to_texture_coordinates * light_projection
+        })
+    }
+}
+impl ShadowMap4D {
+    pub fn new(display: &GlContext, options: &ShadowOptions) -> Self {
+        Self {
+            texture: texture::DepthTexture2dArray::empty_with_format(
+                display,
+                texture::DepthFormat::I24,
+                texture::MipmapsOption::NoMipmap,
+                options.resolution,
+                options.resolution,
+                options.layer_count_4D
+            ).unwrap()
+        }
+    }
+
+    pub fn render(&mut self, display: &GlContext, shaders: &ShaderProgramContainer, world: &World4D, options: &ShadowOptions) -> Option<Shadow4D> {
+        if !options.enabled {
+            return None;
+        }
+        let (light, direction) = find_shadow_caster_4D(world)?;
+
+        if self.texture.dimensions() != (options.resolution, options.resolution) || self.texture.array_size() != options.layer_count_4D {
+            *self = Self::new(display, options);
+        }
+
+        let light_projection = get_light_projection_4D(direction, world.player.get_camera_world_position(), options);
+        let shadow_caster = ShadowCasterMaterial {
+            depth_bias: get_depth_bias(options)
+        };
+        let program = shaders.get_program(ShadowCasterMaterial::PROGRAM_IDS.degenerate_4D);
+        let draw_parameters = get_draw_parameters();
+
+        let shadow_casters: Vec<_> = world.scene.meshes.iter()
+            .filter_map(|(entity, mesh)| Some((world.scene.transforms.get(entity)?, mesh)))
+            .map(|(transform, mesh)| (AffineTransform4D::from(transform), mesh))
+            .collect();
+
+        let layer_count = self.texture.array_size();
+        for layer in 0..layer_count {
+            //the sliced geometry shader slices at x == 0
+            let layer_center = -1.0 + (2 * layer + 1) as f32 / layer_count as f32;
+            let layer_projection = AffineTransform4D {
+                linear_transform: light_projection.linear_transform,
+                translation: light_projection.translation - Vec4::new(layer_center, 0.0, 0.0, 0.0)
+            };
+
+            let layer_image = self.texture.main_level().layer(layer).unwrap();
+            let mut frame_buffer = framebuffer::SimpleFrameBuffer::depth_only(display, layer_image).unwrap();
+            frame_buffer.clear_depth(1.0);
+            for (to_world_transform, mesh) in &shadow_casters {
+                let vertex_block = GlobalVertexBlock4D {
+                    to_world_transform: to_world_transform.std140(),
+                    to_view_transform: AffineTransform4D { linear_transform: Mat4::ZERO, translation: Vec4::W }.std140(),//no perspective division
+                    to_clip_transform: (layer_projection * *to_world_transform).std140(),
+                    normal_matrix: to_world_transform.point_transform_to_normal_transform().std140(),
+                    volume_to_view_transform: AffineTransform3D::IDENTITY.std140(),
+                    volume_to_clip_transform: AffineTransform3D::IDENTITY.std140()
+                };
+                let vertex_block_buffer = vertex_block.get_glium_uniform_buffer(display);
+
+                frame_buffer.draw(
+                    &mesh.vertices,
+                    &mesh.indeces,
+                    program,
+                    &shadow_caster.get_uniforms().add("vertex_uniforms", &vertex_block_buffer),
+                    &draw_parameters
+                ).unwrap();
+            }
+        }
+
+        //from [-1, 1] to [0, 1]
+        let to_texture_coordinates = AffineTransform4D {
+            linear_transform: Mat4::from_diagonal(Vec4::splat(0.5)),
+            translation: Vec4::splat(0.5)
+        };
+        Some(Shadow4D {
+            light,
+            shadow_map_transform: to_texture_coordinates * light_projection
+        })
+    }
+}
+
+fn find_shadow_caster_3D(world: &World3D) -> Option<(EntityId, Vec3)> {
+    world.scene.lights.iter().find_map(|(entity, light)| match *light {
+        Light3D::Directional { direction, casts_shadows: true, .. } => Some((entity, direction.normalize())),
+        _ => None
+    })
+}
+fn find_shadow_caster_4D(world: &World4D) -> Option<(EntityId, Vec4)> {
+    world.scene.lights.iter().find_map(|(entity, light)| match *light {
+        Light4D::Directional { direction, casts_shadows: true, .. } => Some((entity, direction.normalize())),
+        _ => None
+    })
+}
+
+//from world space to a [-1, 1] box around the center, with the light direction as depth (the last axis)
+fn get_light_projection_3D(direction: Vec3, center: Vec3, options: &ShadowOptions) -> AffineTransform3D {
+    let (a, b) = direction.any_orthonormal_pair();
+    let light_basis = Mat3::from_cols(a, b, direction);
+    let scale = Mat3::from_diagonal(Vec3::new(1.0 / options.extent, 1.0 / options.extent, 1.0 / options.depth_range));
+
+    let linear_transform = scale * light_basis.transpose();
+    AffineTransform3D {
+        linear_transform,
+        translation: -(linear_transform * center)
+    }
+}
+fn get_light_projection_4D(direction: Vec4, center: Vec4, options: &ShadowOptions) -> AffineTransform4D {
+    //Gram-Schmidt with the three axes that are the least aligned with the direction, so that they are never almost parallel
+    let mut axes = [Vec4::X, Vec4::Y, Vec4::Z, Vec4::W];
+    axes.sort_by(|a, b| a.dot(direction).abs().total_cmp(&b.dot(direction).abs()));
+    let mut basis = vec![direction];
+    for axis in &axes[0..3] {
+        let orthogonal = basis.iter().fold(*axis, |vector, basis_vector| vector - vector.dot(*basis_vector) * *basis_vector);
+        basis.push(orthogonal.normalize());
+    }
+    let light_basis = Mat4::from_cols(basis[1], basis[2], basis[3], direction);
+    let scale = Mat4::from_diagonal(Vec4::new(1.0 / options.extent, 1.0 / options.extent, 1.0 / options.extent, 1.0 / options.depth_range));
+
+    let linear_transform = scale * light_basis.transpose();
+    AffineTransform4D {
+        linear_transform,
+        translation: -(linear_transform * center)
+    }
+}
+
+//the bias is given in world units, while depth goes from 0 to 1 over the whole depth range
+fn get_depth_bias(options: &ShadowOptions) -> f32 {
+    options.bias / (2.0 * options.depth_range)
+}
+
+fn get_draw_parameters() -> glium::DrawParameters<'static> {
+    glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::draw_parameters::DepthTest::IfLess,
+            write: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    }
+}
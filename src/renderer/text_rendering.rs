@@ -1,5 +1,8 @@
-use glium_glyph::glyph_brush::ab_glyph::FontVec;
+use glium_glyph::glyph_brush::{self, ab_glyph::FontVec};
 use crate::global_data::GlobalData;
+use glam::Vec2;
+use std::f32::consts::TAU;
+use super::GlContext;
 
 pub type LoadedFontRef = glium_glyph::glyph_brush::FontId;
 
@@ -19,7 +22,7 @@ pub struct TextRenderer<'a> {
     pub brush: glium_glyph::GlyphBrush<'a, FontVec>
 }
 impl<'a> TextRenderer<'a> {
-    pub fn new(display: &glium::Display, global_data: &GlobalData) -> Self {
+    pub fn new(display: &GlContext, global_data: &GlobalData) -> Self {
         let mut brush_builder = glium_glyph::GlyphBrushBuilder::using_fonts(vec![]);
         let mut loader = FontLoader::new(&mut brush_builder);
         let container = FontContainer::load_to_new(&mut loader, global_data);
@@ -30,6 +33,49 @@ impl<'a> TextRenderer<'a> {
             brush: brush
         }
     }
+
+    //white text with a black outline, in the info screen font. screen_position is in pixels, from the top left corner
+    pub fn queue_outlined_text(&mut self, text: &str, screen_position: Vec2, global_data: &GlobalData) {
+        let font_size = global_data.options.user.info_screen.font_size;
+
+        let formatted_text = glyph_brush::Text {
+            text,
+            scale: font_size.into(),
+            font_id: self.fonts.info_screen,
+            ..Default::default()
+        };
+        let mut section = glyph_brush::Section {
+            text: vec![formatted_text],
+            ..Default::default()
+        };
+
+        //outline
+        section.text[0].extra.color = [0.0, 0.0, 0.0, 1.0];
+        let outline_size = global_data.options.user.info_screen.relative_outline_size * font_size;
+        let offsets = get_points_on_unit_circle(global_data.options.user.info_screen.outline_quality);
+        for offset in offsets {
+            section.screen_position = (screen_position + outline_size * offset).into();
+
+            self.brush.queue(section.clone());
+        }
+
+        //main text
+        section.text[0].extra.color = [1.0, 1.0, 1.0, 1.0];
+        section.screen_position = screen_position.into();
+        self.brush.queue(section);
+    }
+
+    //like GlyphBrush::draw_queued, but sized to the target instead of the default framebuffer, which headless contexts don't report correctly
+    pub fn draw_queued<S: glium::Surface>(&mut self, display: &GlContext, target: &mut S) {
+        let dimensions = target.get_dimensions();
+        let transform = [
+            [2.0 / (dimensions.0 as f32), 0.0, 0.0, 0.0],
+            [0.0, 2.0 / (dimensions.1 as f32), 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-1.0, -1.0, 0.0, 1.0]
+        ];
+        self.brush.draw_queued_with_transform(transform, display, target);
+    }
 }
 
 struct FontLoader<'l, 'b> {
@@ -58,4 +104,18 @@ impl<'l, 'b> FontLoader<'l, 'b> {
 
         self.brush_builder.add_font(font_vec)
     }
+}
+
+fn get_points_on_unit_circle(count: usize) -> Vec<Vec2> {
+    let rotation = Vec2::from_angle(TAU / count as f32);
+    let mut points = Vec::<Vec2>::with_capacity(count);
+
+    for _ in 0..count {
+        points.push(match points.last() {
+            Some(v) => v.rotate(rotation),
+            None => Vec2::X
+        });
+    }
+
+    points
 }
\ No newline at end of file
@@ -1,31 +1,34 @@
-use glium::PolygonMode;
+use glium::{PolygonMode, Surface};
 use crate::game::world::{World3D, World4D};
 use crate::game::player;
 use crate::global_data::{GlobalData, VisualMode};
-use super::Renderer;
-use super::shading::uniform::{GlobalFragmentBlock3D, GlobalFragmentBlock4D, UniformBlock};
+use super::{Renderer, GlContext};
+use super::shading::uniform::{GlobalFragmentBlock3D, GlobalFragmentBlock4D, UniformBlock, MAX_POINT_LIGHTS, MAX_DIRECTIONAL_LIGHTS};
+use super::shading::uniform::{Std140PointLight3D, Std140PointLight4D, Std140DirectionalLight3D, Std140DirectionalLight4D};
 use super::shading::glsl_conversion::ToStd140;
-use crate::options::AsVector;
-use super::renderable_object::{ObjectDrawContext3D, ObjectDrawContext4D, ObjectDrawTargets};
+use crate::options::{AsVector, UserGraphicsOptions};
+use super::renderable_object::{RenderableObject3D, RenderableObject4D, ObjectDrawContext3D, ObjectDrawContext4D, ObjectDrawTargets};
+use super::shading::abstract_material::ShaderProgramId;
+use super::shading::dynamic_material::DynamicMaterial;
+use crate::game::entity::{EntityId, ComponentStorage};
+use crate::game::light::{Light3D, Light4D};
+use glam::{Vec2, Vec3, Vec4, Mat3, Mat4};
+use crate::game::transform::{AffineTransform3D, AffineTransform4D, matrix4x4};
+use super::shadow_map::{Shadow3D, Shadow4D};
 
 impl Renderer<'_> {
-    pub fn render_objects_3D(
+    pub fn render_objects_3D<S: Surface>(
         &mut self,
-        target: &mut glium::Frame,
-        display: &glium::Display,
+        target: &mut S,
+        display: &GlContext,
         world: &World3D,
         global_data: &GlobalData)
     {
         let inverse_camera_trs_matrix = world.player.get_camera_trs_matrix().inverse();
         let projection_matrix = player::player_projection_matrix_3D(global_data);
 
-        let fragment_block = GlobalFragmentBlock3D {
-            light_position: world.player.get_camera_world_position().std140(),
-            light_color: global_data.options.dev.light.light_color.as_vector().std140(),
-            light_ambient_color: global_data.options.dev.light.ambient_color.as_vector().std140(),
-            light_linear_attenuation: global_data.options.dev.light.linear_attenuation.std140(),
-            light_quadratic_attenuation: global_data.options.dev.light.quadratic_attenuation.std140()
-        };
+        let shadow = self.shadow_map_3D.render(display, &self.shader_programs, world, &global_data.options.dev.shadow);
+        let fragment_block = get_fragment_block_3D(world, global_data, shadow);
         let fragment_block_buffer = fragment_block.get_glium_uniform_buffer(display);
 
         let surface_glium_draw_parameters = glium::DrawParameters {
@@ -63,20 +66,22 @@ impl Renderer<'_> {
                 inverse_camera_trs_matrix,
                 projection_matrix,
                 fragment_block_buffer,
+                shadow_map: &self.shadow_map_3D.texture,
                 surface_glium_draw_parameters,
                 skeleton_glium_draw_parameters,
                 visual_mode: VisualMode::Normal3D,
                 _global_data: global_data
             };
-            self.skeleton_target.setup_for_rendering(display, (0.0, 0.0, 0.0, 0.0));
+            self.skeleton_target.setup_for_rendering(display, target.get_dimensions(), (0.0, 0.0, 0.0, 0.0));
             self.skeleton_target.with_frame_buffer_mut(|skeleton_target|
                 render_objects_simple_visual_mode_3D(world, target, skeleton_target, &object_draw_context)
             );
             self.skeleton_target.blit_onto_with_alpha(target, &self.shader_programs, &self.BLIT_QUAD);
 
             object_draw_context.visual_mode = VisualMode::Degenerate3D;
-            self.skeleton_target.setup_for_rendering(display, (0.0, 0.0, 0.0, 0.0));
-            self.alternate_target.setup_for_rendering(display, (0.0, 0.0, 1.0, 1.0));
+            object_draw_context.inverse_camera_trs_matrix = get_slice_offset_transform_3D(global_data.slice_offset) * inverse_camera_trs_matrix;
+            self.skeleton_target.setup_for_rendering(display, target.get_dimensions(), (0.0, 0.0, 0.0, 0.0));
+            self.alternate_target.setup_for_rendering(display, target.get_dimensions(), (0.0, 0.0, 1.0, 1.0));
             self.alternate_target.with_frame_buffer_mut(|alternate_target|
                 self.skeleton_target.with_frame_buffer_mut(|skeleton_target|
                     render_objects_simple_visual_mode_3D(world, alternate_target, skeleton_target, &object_draw_context))
@@ -94,16 +99,20 @@ impl Renderer<'_> {
             let object_draw_context = ObjectDrawContext3D {
                 display,
                 shaders: &self.shader_programs,
-                inverse_camera_trs_matrix,
+                inverse_camera_trs_matrix: match global_data.visual_mode {
+                    VisualMode::Degenerate3D => get_slice_offset_transform_3D(global_data.slice_offset) * inverse_camera_trs_matrix,
+                    _ => inverse_camera_trs_matrix
+                },
                 projection_matrix,
                 fragment_block_buffer,
+                shadow_map: &self.shadow_map_3D.texture,
                 surface_glium_draw_parameters,
                 skeleton_glium_draw_parameters,
                 visual_mode: global_data.visual_mode,
                 _global_data: global_data
             };
 
-            self.skeleton_target.setup_for_rendering(display, (0.0, 0.0, 0.0, 0.0));
+            self.skeleton_target.setup_for_rendering(display, target.get_dimensions(), (0.0, 0.0, 0.0, 0.0));
             self.skeleton_target.with_frame_buffer_mut(|skeleton_target|
                 render_objects_simple_visual_mode_3D(world, target, skeleton_target, &object_draw_context)
             );
@@ -112,26 +121,25 @@ impl Renderer<'_> {
             }
         }
     }
-    pub fn render_objects_4D(
+    pub fn render_objects_4D<S: Surface>(
         &mut self,
-        target: &mut glium::Frame,
-        display: &glium::Display,
+        target: &mut S,
+        display: &GlContext,
         world: &World4D,
         global_data: &GlobalData)
     {
-        let inverse_camera_trs_matrix = world.player.get_camera_trs_matrix().inverse();
+        let inverse_camera_trs_matrix = match global_data.visual_mode {
+            VisualMode::Projection4D => world.player.get_camera_trs_matrix().inverse(),
+            _ => get_slice_offset_transform_4D(global_data.slice_offset) * world.player.get_camera_trs_matrix().inverse()
+        };
         let projection_matrix = player::player_projection_matrix_4D(global_data);
+        let volume_to_view_transform = player::projection_4D_volume_view_matrix(global_data);
 
-        let fragment_block = GlobalFragmentBlock4D {
-            light_position: world.player.get_camera_world_position().std140(),
-            light_color: global_data.options.dev.light.light_color.as_vector().std140(),
-            light_ambient_color: global_data.options.dev.light.ambient_color.as_vector().std140(),
-            light_linear_attenuation: global_data.options.dev.light.linear_attenuation.std140(),
-            light_quadratic_attenuation: global_data.options.dev.light.quadratic_attenuation.std140()
-        };
+        let shadow = self.shadow_map_4D.render(display, &self.shader_programs, world, &global_data.options.dev.shadow);
+        let fragment_block = get_fragment_block_4D(world, global_data, shadow);
         let fragment_block_buffer = fragment_block.get_glium_uniform_buffer(display);
 
-        let surface_glium_draw_parameters = glium::DrawParameters {
+        let mut surface_glium_draw_parameters = glium::DrawParameters {
             depth: glium::Depth {
                 test: glium::draw_parameters::DepthTest::IfLess,
                 write: true,
@@ -142,6 +150,18 @@ impl Renderer<'_> {
             point_size: if global_data.polygon_mode == PolygonMode::Point { Some(global_data.options.dev.debug.point_size) } else { None },
             .. Default::default()
         };
+        if global_data.visual_mode == VisualMode::Projection4D {
+            //everything that is projected onto the same point has to stay visible
+            surface_glium_draw_parameters.depth = glium::Depth::default();
+            surface_glium_draw_parameters.blend = glium::Blend {
+                color: glium::BlendingFunction::Addition {
+                    source: glium::LinearBlendingFactor::ConstantAlpha,
+                    destination: glium::LinearBlendingFactor::OneMinusConstantAlpha
+                },
+                alpha: glium::BlendingFunction::Max,
+                constant_value: (0.0, 0.0, 0.0, global_data.options.user.graphics.projection_4D_opacity)
+            };
+        }
         let skeleton_glium_draw_parameters = glium::DrawParameters {
             blend: glium::Blend {
                 color: glium::BlendingFunction::Addition {
@@ -158,42 +178,386 @@ impl Renderer<'_> {
             ..Default::default()
         };
 
-        let object_draw_context = ObjectDrawContext4D {
+        let mut object_draw_context = ObjectDrawContext4D {
             display,
             shaders: &self.shader_programs,
             inverse_camera_trs_matrix,
             projection_matrix,
             fragment_block_buffer,
+            shadow_map: &self.shadow_map_4D.texture,
             surface_glium_draw_parameters,
             skeleton_glium_draw_parameters,
+            visual_mode: global_data.visual_mode,
+            volume_to_view_transform,
+            volume_to_clip_transform: player::player_projection_matrix_3D(global_data) * volume_to_view_transform,
             _global_data: global_data
         };
 
-        self.skeleton_target.setup_for_rendering(display, (0.0, 0.0, 0.0, 0.0));
-        self.skeleton_target.with_frame_buffer_mut(|skeleton_target|
-            render_objects_simple_visual_mode_4D(world, target, skeleton_target, &object_draw_context)
-        );
-        if global_data.polygon_mode == PolygonMode::Fill {
-            self.skeleton_target.blit_onto_with_alpha(target, &self.shader_programs, &self.BLIT_QUAD);
+        if global_data.visual_mode == VisualMode::ThickSlice4D {
+            //the layers beside the slice are blended from the outermost to the innermost, then the slice itself is drawn on top
+            for (offset, opacity) in get_thick_slice_layers(&global_data.options.user.graphics) {
+                object_draw_context.inverse_camera_trs_matrix = get_slice_offset_transform_4D(offset) * inverse_camera_trs_matrix;
+                self.skeleton_target.setup_for_rendering(display, target.get_dimensions(), (0.0, 0.0, 0.0, 0.0));
+                self.alternate_target.setup_for_rendering(display, target.get_dimensions(), (0.0, 0.0, 0.0, 0.0));
+                self.alternate_target.with_frame_buffer_mut(|alternate_target|
+                    self.skeleton_target.with_frame_buffer_mut(|skeleton_target|
+                        render_objects_simple_visual_mode_4D(world, alternate_target, skeleton_target, &object_draw_context))
+                );
+                self.alternate_target.blit_onto_with_opacity(target, opacity, &self.shader_programs, &self.BLIT_QUAD);
+            }
+            object_draw_context.inverse_camera_trs_matrix = inverse_camera_trs_matrix;
+        }
+
+        if global_data.visual_mode == VisualMode::Combined4D {
+            //the gaps between the viewports are left black, as separators
+            target.clear_color(0.0, 0.0, 0.0, 1.0);
+            let dimensions = target.get_dimensions();
+            let camera_view_transform = world.player.get_camera_trs_matrix().inverse();
+            for view in get_combined_4D_views(dimensions, global_data) {
+                object_draw_context.inverse_camera_trs_matrix = view.view_change * camera_view_transform;
+                self.skeleton_target.setup_for_rendering(display, dimensions, (0.0, 0.0, 0.0, 0.0));
+                self.alternate_target.setup_for_rendering(display, dimensions, (0.0, 0.0, 1.0, 1.0));
+                self.alternate_target.with_frame_buffer_mut(|alternate_target|
+                    self.skeleton_target.with_frame_buffer_mut(|skeleton_target|
+                        render_objects_simple_visual_mode_4D(world, alternate_target, skeleton_target, &object_draw_context))
+                );
+                self.alternate_target.blit_onto_viewport_with_alpha(target, Some(view.viewport), &self.shader_programs, &self.BLIT_QUAD);
+                if global_data.polygon_mode == PolygonMode::Fill {
+                    self.skeleton_target.blit_onto_viewport_with_alpha(target, Some(view.viewport), &self.shader_programs, &self.BLIT_QUAD);
+                }
+
+                //bottom left corner of the viewport. Text is positioned from the top left corner of the screen
+                let font_size = global_data.options.user.info_screen.font_size;
+                let label_position = Vec2::new(
+                    (view.viewport.left + COMBINED_4D_LABEL_MARGIN) as f32,
+                    (dimensions.1 - view.viewport.bottom - COMBINED_4D_LABEL_MARGIN) as f32 - font_size
+                );
+                self.text_renderer.queue_outlined_text(&view.label, label_position, global_data);
+            }
+            self.text_renderer.draw_queued(display, target);
+        }
+        else {
+            self.skeleton_target.setup_for_rendering(display, target.get_dimensions(), (0.0, 0.0, 0.0, 0.0));
+            self.skeleton_target.with_frame_buffer_mut(|skeleton_target|
+                render_objects_simple_visual_mode_4D(world, target, skeleton_target, &object_draw_context)
+            );
+            if global_data.polygon_mode == PolygonMode::Fill {
+                self.skeleton_target.blit_onto_with_alpha(target, &self.shader_programs, &self.BLIT_QUAD);
+            }
         }
     }
 }
 
+const COMBINED_4D_LABEL_MARGIN: u32 = 5;
+
+//one of the viewports of Combined4D
+struct SliceView {
+    label: String,
+    view_change: AffineTransform4D,//applied after the camera's view transform, which doesn't include the slice offset
+    viewport: glium::Rect
+}
+
+//the viewport of the slice itself, where the camera looks
+pub(super) fn get_combined_4D_main_viewport(dimensions: (u32, u32), global_data: &GlobalData) -> glium::Rect {
+    get_combined_4D_views(dimensions, global_data).swap_remove(0).viewport
+}
+
+//a 2x2 grid: the slice itself, the slices beside it along the degenerate x axis, and the slice along the (screen) y axis through the camera
+fn get_combined_4D_views(dimensions: (u32, u32), global_data: &GlobalData) -> Vec<SliceView> {
+    let offset = global_data.options.user.graphics.combined_4D_slice_offset;
+    let (width, height) = (dimensions.0 / 2, dimensions.1 / 2);
+    //leaves a one pixel gap between the viewports
+    let get_viewport = |column: u32, row: u32| glium::Rect {
+        left: column * width + column,
+        bottom: (1 - row) * height + (1 - row),
+        width: width - 1,
+        height: height - 1
+    };
+    //view space x becomes screen right, and y becomes the degenerate axis
+    let swap_x_y = AffineTransform4D {
+        linear_transform: matrix4x4![
+            0.0, 1.0, 0.0, 0.0,
+           -1.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0, 1.0
+        ],
+        translation: Vec4::ZERO
+    };
+
+    vec![
+        SliceView {
+            label: format!("Slice (x = {:.2})", global_data.slice_offset),
+            view_change: get_slice_offset_transform_4D(global_data.slice_offset),
+            viewport: get_viewport(0, 0)
+        },
+        SliceView {
+            label: String::from("Side slice (y = 0)"),
+            view_change: swap_x_y,
+            viewport: get_viewport(1, 0)
+        },
+        SliceView {
+            label: format!("x = {:.2}", global_data.slice_offset - offset),
+            view_change: get_slice_offset_transform_4D(global_data.slice_offset - offset),
+            viewport: get_viewport(0, 1)
+        },
+        SliceView {
+            label: format!("x = {:.2}", global_data.slice_offset + offset),
+            view_change: get_slice_offset_transform_4D(global_data.slice_offset + offset),
+            viewport: get_viewport(1, 1)
+        }
+    ]
+}
+
 fn render_objects_simple_visual_mode_3D<A: glium::Surface, B: glium::Surface>(world: &World3D, surface_target: &mut A, skeleton_target: &mut B, context: &ObjectDrawContext3D) {
     let mut targets = ObjectDrawTargets { surface_target, skeleton_target };
 
-    for object in &world.static_scene {
-        object.render(&mut targets, context);
-    }
+    let get_program_ids = |material: &DynamicMaterial| {
+        let ids = material.program_ids();
+        match context.visual_mode {
+            VisualMode::Degenerate3D => (ids.degenerate_3D, ids.degenerate_3D_skeleton),
+            _ => (ids.normal_3D, ids.normal_3D_skeleton)
+        }
+    };
 
-    world.floor.render(&mut targets, context);
+    for entity in get_draw_order(&world.scene.materials, get_program_ids) {
+        let (Some(transform), Some(mesh), Some(material)) = (world.scene.transforms.get(entity), world.scene.meshes.get(entity), world.scene.materials.get(entity)) else {
+            continue;
+        };
+
+        RenderableObject3D {
+            transform: transform.into(),
+            mesh,
+            material
+        }.render(&mut targets, context);
+    }
 }
 fn render_objects_simple_visual_mode_4D<A: glium::Surface, B: glium::Surface>(world: &World4D, surface_target: &mut A, skeleton_target: &mut B, context: &ObjectDrawContext4D) {
     let mut targets = ObjectDrawTargets { surface_target, skeleton_target };
 
-    for object in &world.static_scene {
-        object.render(&mut targets, context);
+    let get_program_ids = |material: &DynamicMaterial| {
+        let ids = material.program_ids();
+        match context.visual_mode {
+            VisualMode::Projection4D => (ids.projected_4D, ids.projected_4D_skeleton),
+            _ => (ids.degenerate_4D, ids.degenerate_4D_skeleton)
+        }
+    };
+
+    for entity in get_draw_order(&world.scene.materials, get_program_ids) {
+        let (Some(transform), Some(mesh), Some(material)) = (world.scene.transforms.get(entity), world.scene.meshes.get(entity), world.scene.materials.get(entity)) else {
+            continue;
+        };
+
+        RenderableObject4D {
+            transform: transform.into(),
+            mesh,
+            material
+        }.render(&mut targets, context);
+    }
+}
+
+//(offset along the view x axis, opacity) of the layers of ThickSlice4D, from the outermost to the innermost
+fn get_thick_slice_layers(options: &UserGraphicsOptions) -> Vec<(f32, f32)> {
+    let layer_count = options.thick_slice_layer_count;
+    (1..=layer_count).rev()
+        .flat_map(|i| {
+            let offset = options.thick_slice_half_thickness * i as f32 / layer_count as f32;
+            let opacity = options.thick_slice_opacity * (layer_count - i + 1) as f32 / layer_count as f32;
+            [(offset, opacity), (-offset, opacity)]
+        })
+        .collect()
+}
+
+//moves the slicing plane (view space x == 0) to view space x == offset
+fn get_slice_offset_transform_3D(offset: f32) -> AffineTransform3D {
+    AffineTransform3D {
+        linear_transform: Mat3::IDENTITY,
+        translation: Vec3::new(-offset, 0.0, 0.0)
+    }
+}
+//moves the slicing hyperplane (view space x == 0) to view space x == offset
+fn get_slice_offset_transform_4D(offset: f32) -> AffineTransform4D {
+    AffineTransform4D {
+        linear_transform: Mat4::IDENTITY,
+        translation: Vec4::new(-offset, 0.0, 0.0, 0.0)
+    }
+}
+
+//batches entities by (surface, skeleton) program, to minimize program switches
+fn get_draw_order<F>(materials: &ComponentStorage<DynamicMaterial>, get_program_ids: F) -> Vec<EntityId>
+    where F: Fn(&DynamicMaterial) -> (ShaderProgramId, ShaderProgramId)
+{
+    let mut entities: Vec<(EntityId, (ShaderProgramId, ShaderProgramId))> = materials.iter()
+        .map(|(entity, material)| (entity, get_program_ids(material)))
+        .collect();
+    entities.sort_by_key(|&(entity, program_ids)| (program_ids, entity));
+
+    entities.iter()
+        .map(|&(entity, _)| entity)
+        .collect()
+}
+
+fn get_fragment_block_3D(world: &World3D, global_data: &GlobalData, shadow: Option<Shadow3D>) -> GlobalFragmentBlock3D {
+    let options = &global_data.options.dev.light;
+    let camera_position = world.player.get_camera_world_position();
+
+    let mut point_lights = Vec::new();//with their distance to the camera
+    let mut directional_lights = Vec::new();
+    if options.camera_light {
+        point_lights.push((0.0, Std140PointLight3D {
+            position: camera_position.std140(),
+            color: options.light_color.as_vector().std140(),
+            linear_attenuation: options.linear_attenuation.std140(),
+            quadratic_attenuation: options.quadratic_attenuation.std140()
+        }));
+    }
+    for (entity, light) in world.scene.lights.iter() {
+        match *light {
+            Light3D::Point { color, linear_attenuation, quadratic_attenuation } => {
+                let Some(transform) = world.scene.transforms.get(entity) else {
+                    continue;
+                };
+                point_lights.push((transform.position.distance(camera_position), Std140PointLight3D {
+                    position: transform.position.std140(),
+                    color: color.std140(),
+                    linear_attenuation: linear_attenuation.std140(),
+                    quadratic_attenuation: quadratic_attenuation.std140()
+                }));
+            },
+            Light3D::Directional { direction, color, .. } => {
+                let directional_light = Std140DirectionalLight3D {
+                    direction: direction.normalize().std140(),
+                    color: color.std140()
+                };
+                //the shader expects the shadow casting light to be the first one
+                if shadow.is_some_and(|shadow| shadow.light == entity) {
+                    directional_lights.insert(0, directional_light);
+                }
+                else {
+                    directional_lights.push(directional_light);
+                }
+            }
+        }
+    }
+    point_lights.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    let point_lights: Vec<Std140PointLight3D> = point_lights.into_iter().map(|(_, light)| light).collect();
+
+    let point_light_count = point_lights.len().min(options.max_point_lights).min(MAX_POINT_LIGHTS);
+    let directional_light_count = directional_lights.len().min(options.max_directional_lights).min(MAX_DIRECTIONAL_LIGHTS);
+    let empty_point_light = Std140PointLight3D {
+        position: Vec3::ZERO.std140(),
+        color: Vec3::ZERO.std140(),
+        linear_attenuation: 0.0.std140(),
+        quadratic_attenuation: 0.0.std140()
+    };
+    let empty_directional_light = Std140DirectionalLight3D {
+        direction: Vec3::ZERO.std140(),
+        color: Vec3::ZERO.std140()
+    };
+
+    let shadow_map_transform = match shadow {
+        Some(shadow) => shadow.shadow_map_transform,
+        None => AffineTransform3D::IDENTITY
+    };
+
+    GlobalFragmentBlock3D {
+        point_lights: pad_lights(&point_lights[..point_light_count], empty_point_light).std140(),
+        directional_lights: pad_lights(&directional_lights[..directional_light_count], empty_directional_light).std140(),
+        shadow_transform: shadow_map_transform.std140(),
+        camera_position: camera_position.std140(),
+        light_ambient_color: options.ambient_color.as_vector().std140(),
+        point_light_count: (point_light_count as i32).std140(),
+        directional_light_count: (directional_light_count as i32).std140(),
+        has_shadow: (shadow.is_some() as i32).std140()
     }
+}
+fn get_fragment_block_4D(world: &World4D, global_data: &GlobalData, shadow: Option<Shadow4D>) -> GlobalFragmentBlock4D {
+    let options = &global_data.options.dev.light;
+    let fog_options = &global_data.options.dev.fog;
+    let camera_position = world.player.get_camera_world_position();
 
-    world.floor.render(&mut targets, context);
-}
\ No newline at end of file
+    let mut point_lights = Vec::new();//with their distance to the camera
+    let mut directional_lights = Vec::new();
+    if options.camera_light {
+        point_lights.push((0.0, Std140PointLight4D {
+            position: camera_position.std140(),
+            color: options.light_color.as_vector().std140(),
+            linear_attenuation: options.linear_attenuation.std140(),
+            quadratic_attenuation: options.quadratic_attenuation.std140()
+        }));
+    }
+    for (entity, light) in world.scene.lights.iter() {
+        match *light {
+            Light4D::Point { color, linear_attenuation, quadratic_attenuation } => {
+                let Some(transform) = world.scene.transforms.get(entity) else {
+                    continue;
+                };
+                point_lights.push((transform.position.distance(camera_position), Std140PointLight4D {
+                    position: transform.position.std140(),
+                    color: color.std140(),
+                    linear_attenuation: linear_attenuation.std140(),
+                    quadratic_attenuation: quadratic_attenuation.std140()
+                }));
+            },
+            Light4D::Directional { direction, color, .. } => {
+                let directional_light = Std140DirectionalLight4D {
+                    direction: direction.normalize().std140(),
+                    color: color.std140()
+                };
+                //the shader expects the shadow casting light to be the first one
+                if shadow.is_some_and(|shadow| shadow.light == entity) {
+                    directional_lights.insert(0, directional_light);
+                }
+                else {
+                    directional_lights.push(directional_light);
+                }
+            }
+        }
+    }
+    point_lights.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    let point_lights: Vec<Std140PointLight4D> = point_lights.into_iter().map(|(_, light)| light).collect();
+
+    let point_light_count = point_lights.len().min(options.max_point_lights).min(MAX_POINT_LIGHTS);
+    let directional_light_count = directional_lights.len().min(options.max_directional_lights).min(MAX_DIRECTIONAL_LIGHTS);
+    let empty_point_light = Std140PointLight4D {
+        position: Vec4::ZERO.std140(),
+        color: Vec3::ZERO.std140(),
+        linear_attenuation: 0.0.std140(),
+        quadratic_attenuation: 0.0.std140()
+    };
+    let empty_directional_light = Std140DirectionalLight4D {
+        direction: Vec4::ZERO.std140(),
+        color: Vec3::ZERO.std140()
+    };
+
+    let shadow_map_transform = match shadow {
+        Some(shadow) => shadow.shadow_map_transform,
+        None => AffineTransform4D::IDENTITY
+    };
+
+    GlobalFragmentBlock4D {
+        point_lights: pad_lights(&point_lights[..point_light_count], empty_point_light).std140(),
+        directional_lights: pad_lights(&directional_lights[..directional_light_count], empty_directional_light).std140(),
+        shadow_transform: shadow_map_transform.std140(),
+        camera_position: camera_position.std140(),
+        light_ambient_color: options.ambient_color.as_vector().std140(),
+        point_light_count: (point_light_count as i32).std140(),
+        directional_light_count: (directional_light_count as i32).std140(),
+        has_shadow: (shadow.is_some() as i32).std140(),
+        slice_normal: world.player.get_camera_world_orientation().x_axis.std140(),
+        fog_color: fog_options.color.as_vector().std140(),
+        slice_tint_positive_color: fog_options.slice_tint_positive_color.as_vector().std140(),
+        slice_tint_negative_color: fog_options.slice_tint_negative_color.as_vector().std140(),
+        fog_start: fog_options.start.std140(),
+        fog_end: fog_options.end.std140(),
+        slice_tint_distance: fog_options.slice_tint_distance.std140(),
+        slice_tint_strength: fog_options.slice_tint_strength.std140(),
+        fog_enabled: (fog_options.enabled as i32).std140(),
+        slice_tint_enabled: (fog_options.slice_tint as i32).std140(),
+        slice_offset: global_data.slice_offset.std140()
+    }
+}
+
+//uniform arrays have a fixed size, so the unused slots are filled with empty lights
+fn pad_lights<T: Copy, const N: usize>(lights: &[T], empty_light: T) -> [T; N] {
+    std::array::from_fn(|i| lights.get(i).copied().unwrap_or(empty_light))
+}
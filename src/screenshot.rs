@@ -0,0 +1,58 @@
+use crate::global_data::{GlobalData, VisualMode};
+use crate::game::world::Multiverse;
+use crate::game::player::{Player3D, Player4D};
+use crate::options::Options;
+use crate::renderer::image_file::Image;
+use crate::clock::get_timestamp;
+use serde::Serialize;
+use std::path::Path;
+use std::fs;
+
+//everything needed to reproduce the view in a screenshot
+#[derive(Serialize)]
+struct ScreenshotSidecar<'a> {
+    visual_mode: VisualMode,
+    slice_offset: f32,
+    resolution: [u32; 2],
+    player_3D: &'a Player3D,
+    player_4D: &'a Player4D,
+    options: &'a Options
+}
+
+//saves the last finished frame, so call after Renderer::render_frame
+pub fn save_screenshot(display: &glium::Display, multiverse: &Multiverse, global_data: &GlobalData) {
+    let options = &global_data.options.user.screenshots;
+    let image = Image::from_raw(display.read_front_buffer().unwrap());
+
+    let directory = Path::new(&options.directory);
+    if let Err(error) = fs::create_dir_all(directory) {
+        eprintln!("Could not create screenshot directory {}: {error}", directory.display());
+        return;
+    }
+    let image_path = directory.join(format!("screenshot_{}.png", get_timestamp()));
+    if let Err(error) = image.save_png(&image_path) {
+        eprintln!("Could not save screenshot to {}: {error}", image_path.display());
+        return;
+    }
+    println!("Screenshot saved to {}", image_path.display());
+
+    if options.write_sidecar {
+        let sidecar = ScreenshotSidecar {
+            visual_mode: global_data.visual_mode,
+            slice_offset: global_data.slice_offset,
+            resolution: [image.dimensions.0, image.dimensions.1],
+            player_3D: &multiverse.world_3D.player,
+            player_4D: &multiverse.world_4D.player,
+            options: &global_data.options
+        };
+        let sidecar_path = image_path.with_extension("json");
+        if let Err(error) = write_sidecar(&sidecar, &sidecar_path) {
+            eprintln!("Could not save screenshot sidecar to {}: {error}", sidecar_path.display());
+        }
+    }
+}
+
+fn write_sidecar(sidecar: &ScreenshotSidecar, path: &Path) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(sidecar)?;
+    fs::write(path, json)
+}